@@ -0,0 +1,182 @@
+//! A procedural macro that generates an `ink-wrapper` contract wrapper at compile time.
+//!
+//! ```ignore
+//! ink_wrapper_macro::contract!(
+//!     mod psp22,
+//!     metadata = "../psp22/target/ink/psp22.json",
+//!     wasm = "../psp22/target/ink/psp22.wasm"
+//! );
+//! ```
+//!
+//! expands to a `mod psp22 { ... }` with the same contents `ink-wrapper -m ... --wasm-path ...` would print. Paths are
+//! relative to the root of the crate calling the macro (`CARGO_MANIFEST_DIR`).
+use std::path::PathBuf;
+
+use ink_wrapper::{generate_with_options, metadata::ContractMetadata, Options};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, Error, Ident, LitBool, LitStr, Result, Token, Visibility,
+};
+
+/// Generates a module wrapping the contract described by the given metadata file.
+///
+/// Takes the module to generate (`mod name` or `pub mod name`), followed by `metadata = "path"`, an optional
+/// `wasm = "path"`, an optional `embed_wasm = true` (like `--embed-wasm`, embedding the WASM of a `.contract` bundle
+/// given as the metadata) and an optional `flatten_results = true` (like `--flatten-results`). If the WASM is given
+/// either way, the generated module will contain an `upload()` function. A missing or malformed metadata file is
+/// reported as a compilation error.
+#[proc_macro]
+pub fn contract(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as ContractInput);
+
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// The parsed arguments of the `contract!` macro.
+struct ContractInput {
+    visibility: Visibility,
+    name: Ident,
+    metadata: LitStr,
+    wasm: Option<LitStr>,
+    embed_wasm: Option<LitBool>,
+    flatten_results: bool,
+}
+
+impl Parse for ContractInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let visibility = input.parse()?;
+        input.parse::<Token![mod]>()?;
+        let name: Ident = input.parse()?;
+
+        let mut metadata = None;
+        let mut wasm = None;
+        let mut embed_wasm = None;
+        let mut flatten_results = None;
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+
+            if key == "flatten_results" || key == "embed_wasm" {
+                let value: LitBool = input.parse()?;
+                let slot = if key == "flatten_results" {
+                    &mut flatten_results
+                } else {
+                    &mut embed_wasm
+                };
+                if slot.replace(value).is_some() {
+                    return Err(Error::new(key.span(), "Duplicate argument"));
+                }
+                continue;
+            }
+
+            let value: LitStr = input.parse()?;
+            let slot = match key.to_string().as_str() {
+                "metadata" => &mut metadata,
+                "wasm" => &mut wasm,
+                _ => {
+                    return Err(Error::new(
+                        key.span(),
+                        "Unknown argument, expected `metadata`, `wasm`, `embed_wasm` or `flatten_results`",
+                    ))
+                }
+            };
+
+            if slot.replace(value).is_some() {
+                return Err(Error::new(key.span(), "Duplicate argument"));
+            }
+        }
+
+        let metadata = metadata.ok_or_else(|| {
+            Error::new(
+                name.span(),
+                "Missing the path to the metadata file: `metadata = \"...\"`",
+            )
+        })?;
+
+        Ok(Self {
+            visibility,
+            name,
+            metadata,
+            wasm,
+            embed_wasm,
+            flatten_results: flatten_results.map_or(false, |value| value.value),
+        })
+    }
+}
+
+fn expand(input: ContractInput) -> Result<TokenStream> {
+    let metadata_path = resolve_path(&input.metadata)?;
+    let mut metadata = ContractMetadata::from_file(&metadata_path)
+        .map_err(|e| Error::new(input.metadata.span(), format!("{:#}", e)))?;
+
+    match &input.embed_wasm {
+        Some(embed_wasm) if embed_wasm.value => {
+            if input.wasm.is_some() {
+                return Err(Error::new(
+                    embed_wasm.span(),
+                    "`embed_wasm` can't be used together with `wasm`",
+                ));
+            }
+            if metadata.wasm.is_none() {
+                return Err(Error::new(
+                    input.metadata.span(),
+                    "Not a .contract bundle with the WASM, which `embed_wasm` needs",
+                ));
+            }
+        }
+        _ => metadata.wasm = None,
+    }
+
+    let wasm_path = input.wasm.as_ref().map(resolve_path).transpose()?;
+    if let (Some(wasm), Some(wasm_path)) = (&input.wasm, &wasm_path) {
+        metadata
+            .verify_wasm(wasm_path)
+            .map_err(|e| Error::new(wasm.span(), format!("{:#}", e)))?;
+    }
+
+    let options = Options {
+        flatten_results: input.flatten_results,
+        ..Options::default()
+    };
+    let (wrapper, _) = generate_with_options(&metadata, wasm_path, &options)
+        .map_err(|e| Error::new(input.metadata.span(), e.to_string()))?;
+
+    let visibility = input.visibility;
+    let name = input.name;
+
+    // `include_bytes!` makes the compiler track the metadata file, so the wrapper is regenerated when it changes. The
+    // WASM is already tracked by the `include_bytes!` in `upload()`.
+    Ok(quote! {
+        #visibility mod #name {
+            const _: &[u8] = include_bytes!(#metadata_path);
+
+            #wrapper
+        }
+    })
+}
+
+/// Resolves a path given to the macro against the root of the calling crate and checks that it exists.
+fn resolve_path(path: &LitStr) -> Result<String> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| Error::new(path.span(), "CARGO_MANIFEST_DIR is not set"))?;
+    let resolved = PathBuf::from(manifest_dir).join(path.value());
+
+    if !resolved.is_file() {
+        return Err(Error::new(
+            path.span(),
+            format!("File not found: {}", resolved.display()),
+        ));
+    }
+
+    Ok(resolved.to_string_lossy().into_owned())
+}
@@ -0,0 +1,102 @@
+use crate::{
+    ContractExecResult, ContractInstantiateResult, ContractReadResult, ExecCall, InstantiateCall,
+    QueryArgs, UploadCall, UploadResult,
+};
+
+/// An asynchronous counterpart of the drink `Connection` trait, implemented by backends for live chains (e.g.
+/// `subxt_types::SignedConnection`).
+///
+/// The methods take `&self` and return `Send` futures, so a connection can be shared between tasks, e.g. ones started
+/// with `tokio::spawn`. Code that should also run against drink can use `LocalAsyncConnection` instead.
+#[async_trait::async_trait]
+pub trait AsyncConnection: Sync {
+    /// The type of account ids (contract addresses) on the chain.
+    type AccountId;
+    /// The type of code hashes on the chain.
+    type Hash;
+    /// The error returned by the backend.
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    async fn upload_code(&self, call: UploadCall) -> Result<UploadResult<Self::Hash>, Self::Error>;
+
+    async fn instantiate<T: Send>(
+        &self,
+        call: InstantiateCall<T>,
+    ) -> Result<ContractInstantiateResult<Self::AccountId>, Self::Error>;
+
+    async fn execute<T: scale::Decode + Send + std::fmt::Debug>(
+        &self,
+        call: ExecCall<T>,
+    ) -> Result<ContractExecResult<T>, Self::Error>;
+
+    /// Like `exec`, but does not commit changes
+    async fn query<T: scale::Decode + Send + std::fmt::Debug>(
+        &self,
+        call: impl Into<QueryArgs<T>> + Send,
+    ) -> Result<ContractReadResult<T>, Self::Error>;
+}
+
+/// `AsyncConnection` with futures that are not required to be `Send`, implemented by every `AsyncConnection` and by
+/// `drink_types::AsyncAdapter`.
+///
+/// Code written against this trait runs both against a live chain and against drink, where a session cannot be moved
+/// between threads.
+#[async_trait::async_trait(?Send)]
+pub trait LocalAsyncConnection {
+    /// The type of account ids (contract addresses) on the chain.
+    type AccountId;
+    /// The type of code hashes on the chain.
+    type Hash;
+    /// The error returned by the backend.
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    async fn upload_code(&self, call: UploadCall) -> Result<UploadResult<Self::Hash>, Self::Error>;
+
+    async fn instantiate<T: Send>(
+        &self,
+        call: InstantiateCall<T>,
+    ) -> Result<ContractInstantiateResult<Self::AccountId>, Self::Error>;
+
+    async fn execute<T: scale::Decode + Send + std::fmt::Debug>(
+        &self,
+        call: ExecCall<T>,
+    ) -> Result<ContractExecResult<T>, Self::Error>;
+
+    /// Like `exec`, but does not commit changes
+    async fn query<T: scale::Decode + Send + std::fmt::Debug>(
+        &self,
+        call: impl Into<QueryArgs<T>>,
+    ) -> Result<ContractReadResult<T>, Self::Error>;
+}
+
+#[async_trait::async_trait(?Send)]
+impl<C: AsyncConnection> LocalAsyncConnection for C {
+    type AccountId = C::AccountId;
+    type Hash = C::Hash;
+    type Error = C::Error;
+
+    async fn upload_code(&self, call: UploadCall) -> Result<UploadResult<Self::Hash>, Self::Error> {
+        AsyncConnection::upload_code(self, call).await
+    }
+
+    async fn instantiate<T: Send>(
+        &self,
+        call: InstantiateCall<T>,
+    ) -> Result<ContractInstantiateResult<Self::AccountId>, Self::Error> {
+        AsyncConnection::instantiate(self, call).await
+    }
+
+    async fn execute<T: scale::Decode + Send + std::fmt::Debug>(
+        &self,
+        call: ExecCall<T>,
+    ) -> Result<ContractExecResult<T>, Self::Error> {
+        AsyncConnection::execute(self, call).await
+    }
+
+    async fn query<T: scale::Decode + Send + std::fmt::Debug>(
+        &self,
+        call: impl Into<QueryArgs<T>>,
+    ) -> Result<ContractReadResult<T>, Self::Error> {
+        AsyncConnection::query(self, call.into()).await
+    }
+}
@@ -0,0 +1,68 @@
+use std::convert::Infallible;
+
+use crate::InkLangError;
+
+/// Everything that can go wrong with a contract call, flattened into a single error.
+#[derive(Debug, thiserror::Error)]
+pub enum CallError<E> {
+    /// ink! failed to dispatch the message, e.g. because of an unknown selector.
+    #[error("Message dispatch failed: {0}")]
+    Lang(InkLangError),
+    /// The message returned an error.
+    #[error("The contract returned an error: {0:?}")]
+    Contract(E),
+    /// The call reverted without returning an error, so its changes were rolled back.
+    #[error("The call reverted")]
+    Reverted,
+    /// The backend failed to make the call.
+    #[error("Backend error: {0}")]
+    Backend(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl<E> CallError<E> {
+    /// Wraps an error of the backend.
+    pub fn backend(error: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self::Backend(Box::new(error))
+    }
+}
+
+/// The output of a message in wrappers generated with flattened results, which can be turned into the value returned by
+/// the message or a [CallError].
+pub trait MessageOutput {
+    /// The value returned by the message.
+    type Value;
+    /// The error returned by the message, `Infallible` for messages that don't return a `Result`.
+    type Error;
+
+    fn into_result(self) -> Result<Self::Value, CallError<Self::Error>>;
+}
+
+/// The output of a message returning `T`, decoded from the `Result<T, LangError>` ink! returns.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+pub struct Flat<T>(pub Result<T, InkLangError>);
+
+impl<T> MessageOutput for Flat<T> {
+    type Value = T;
+    type Error = Infallible;
+
+    fn into_result(self) -> Result<T, CallError<Infallible>> {
+        self.0.map_err(CallError::Lang)
+    }
+}
+
+/// The output of a message returning `Result<T, E>`, decoded from the `Result<Result<T, E>, LangError>` ink! returns.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+pub struct FlatResult<T, E>(pub Result<Result<T, E>, InkLangError>);
+
+impl<T, E> MessageOutput for FlatResult<T, E> {
+    type Value = T;
+    type Error = E;
+
+    fn into_result(self) -> Result<T, CallError<E>> {
+        match self.0 {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(error)) => Err(CallError::Contract(error)),
+            Err(error) => Err(CallError::Lang(error)),
+        }
+    }
+}
@@ -1,6 +1,7 @@
-use std::marker::PhantomData;
+use std::{fmt::Debug, marker::PhantomData};
 
 use ink_primitives::AccountId;
+use sp_weights::Weight;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum TxStatus {
@@ -10,6 +11,17 @@ pub enum TxStatus {
     Submitted,
 }
 
+/// Settings of a single call that override the defaults of the connection making it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CallOverrides {
+    /// The gas limit of the call.
+    pub gas_limit: Option<Weight>,
+    /// The maximum storage deposit that can be charged for the call.
+    pub storage_deposit_limit: Option<u128>,
+    /// The account making the call.
+    pub caller: Option<AccountId>,
+}
+
 /// Represents a call to a contract constructor.
 #[derive(Debug, Clone)]
 pub struct InstantiateCall<T: Send> {
@@ -23,6 +35,8 @@ pub struct InstantiateCall<T: Send> {
     pub value: u128,
     /// The tx_status to wait on.
     pub tx_status: TxStatus,
+    /// The call-specific overrides of the connection's defaults.
+    pub overrides: CallOverrides,
     /// A marker for the type of contract to instantiate.
     _contract: PhantomData<T>,
 }
@@ -36,6 +50,7 @@ impl<T: Send> InstantiateCall<T> {
             salt: vec![],
             value: 0,
             tx_status: TxStatus::Finalized,
+            overrides: Default::default(),
             _contract: Default::default(),
         }
     }
@@ -51,6 +66,24 @@ impl<T: Send> InstantiateCall<T> {
         self.tx_status = tx_status;
         self
     }
+
+    /// Set the gas limit, instead of the connection's default.
+    pub fn with_gas_limit(mut self, gas_limit: Weight) -> Self {
+        self.overrides.gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Limit the storage deposit that can be charged for the call.
+    pub fn with_storage_deposit_limit(mut self, limit: u128) -> Self {
+        self.overrides.storage_deposit_limit = Some(limit);
+        self
+    }
+
+    /// Make the call on behalf of `caller`, instead of the connection's default account.
+    pub fn as_caller(mut self, caller: AccountId) -> Self {
+        self.overrides.caller = Some(caller);
+        self
+    }
 }
 
 /// Represents a contract call to a payable constructor that still needs the value transferred to be specified.
@@ -93,10 +126,27 @@ pub struct ExecCall<T> {
     pub value: u128,
     /// The tx_status to wait on.
     pub tx_status: TxStatus,
+    /// The call-specific overrides of the connection's defaults.
+    pub overrides: CallOverrides,
+    /// Decodes the error of the message from the data returned by the call, see `with_error_type`.
+    pub error_decoder: Option<ErrorDecoder>,
     /// A marker for the type to decode the result into.
     _return_type: PhantomData<T>,
 }
 
+/// Decodes the error returned by a message from the data returned by the call, if the call returned one.
+pub type ErrorDecoder = fn(&[u8]) -> Option<Box<dyn Debug + Send + Sync>>;
+
+/// Returns the `ErrorDecoder` of a message returning `Result<_, E>`, which ink! wraps in `Result<_, LangError>`.
+fn error_decoder<E: scale::Decode + Debug + Send + Sync + 'static>() -> ErrorDecoder {
+    |data| match data {
+        [0, 1, error @ ..] => E::decode(&mut &error[..])
+            .ok()
+            .map(|error| Box::new(error) as Box<dyn Debug + Send + Sync>),
+        _ => None,
+    }
+}
+
 impl<T: scale::Decode + Send> ExecCall<T> {
     /// Create a new exec call.
     pub fn new(account_id: AccountId, data: Vec<u8>) -> Self {
@@ -105,14 +155,57 @@ impl<T: scale::Decode + Send> ExecCall<T> {
             data,
             value: 0,
             tx_status: TxStatus::Finalized,
+            overrides: Default::default(),
+            error_decoder: None,
             _return_type: Default::default(),
         }
     }
 
+    /// Declare that the message returns `Result<_, E>`, so that the error of a reverted call can be decoded (e.g. by
+    /// `StrictExecution`). The generated wrappers call it for such messages.
+    pub fn with_error_type<E: scale::Decode + Debug + Send + Sync + 'static>(mut self) -> Self {
+        self.error_decoder = Some(error_decoder::<E>());
+        self
+    }
+
     pub fn with_tx_status(mut self, tx_status: TxStatus) -> Self {
         self.tx_status = tx_status;
         self
     }
+
+    /// Set the gas limit, instead of the connection's default.
+    pub fn with_gas_limit(mut self, gas_limit: Weight) -> Self {
+        self.overrides.gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Limit the storage deposit that can be charged for the call.
+    pub fn with_storage_deposit_limit(mut self, limit: u128) -> Self {
+        self.overrides.storage_deposit_limit = Some(limit);
+        self
+    }
+
+    /// Make the call on behalf of `caller`, instead of the connection's default account.
+    pub fn as_caller(mut self, caller: AccountId) -> Self {
+        self.overrides.caller = Some(caller);
+        self
+    }
+}
+
+#[cfg(feature = "drink")]
+impl<T> ExecCall<T> {
+    /// Returns the same call, with its result decoded into `U` instead.
+    pub(crate) fn decoded_as<U>(self) -> ExecCall<U> {
+        ExecCall {
+            account_id: self.account_id,
+            data: self.data,
+            value: self.value,
+            tx_status: self.tx_status,
+            overrides: self.overrides,
+            error_decoder: self.error_decoder,
+            _return_type: PhantomData,
+        }
+    }
 }
 
 /// Reperesents a contract call to a payable method that still needs the value transferred to be specified.
@@ -123,6 +216,8 @@ pub struct ExecCallNeedsValue<T> {
     pub account_id: AccountId,
     /// The encoded data of the call.
     pub data: Vec<u8>,
+    /// Decodes the error of the message from the data returned by the call, see `with_error_type`.
+    pub error_decoder: Option<ErrorDecoder>,
     /// A marker for the type to decode the result into.
     _return_type: PhantomData<T>,
 }
@@ -133,14 +228,22 @@ impl<T: scale::Decode + Send> ExecCallNeedsValue<T> {
         Self {
             account_id,
             data,
+            error_decoder: None,
             _return_type: Default::default(),
         }
     }
 
+    /// Like `ExecCall::with_error_type`.
+    pub fn with_error_type<E: scale::Decode + Debug + Send + Sync + 'static>(mut self) -> Self {
+        self.error_decoder = Some(error_decoder::<E>());
+        self
+    }
+
     /// Set the value to be sent with the call.
     pub fn with_value(self, value: u128) -> ExecCall<T> {
         ExecCall {
             value,
+            error_decoder: self.error_decoder,
             ..ExecCall::new(self.account_id, self.data)
         }
     }
@@ -155,6 +258,8 @@ pub struct ReadCall<T: scale::Decode + Send> {
     pub data: Vec<u8>,
     /// The value to be sent with the call.
     pub value: u128,
+    /// The call-specific overrides of the connection's defaults.
+    pub overrides: CallOverrides,
     /// A marker for the type to decode the result into.
     _return_type: PhantomData<T>,
 }
@@ -166,9 +271,28 @@ impl<T: scale::Decode + Send> ReadCall<T> {
             account_id,
             data,
             value: 0,
+            overrides: Default::default(),
             _return_type: Default::default(),
         }
     }
+
+    /// Set the gas limit, instead of the connection's default.
+    pub fn with_gas_limit(mut self, gas_limit: Weight) -> Self {
+        self.overrides.gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Limit the storage deposit that can be charged for the call.
+    pub fn with_storage_deposit_limit(mut self, limit: u128) -> Self {
+        self.overrides.storage_deposit_limit = Some(limit);
+        self
+    }
+
+    /// Make the call on behalf of `caller`, instead of the connection's default account.
+    pub fn as_caller(mut self, caller: AccountId) -> Self {
+        self.overrides.caller = Some(caller);
+        self
+    }
 }
 
 pub struct ReadCallNeedsValue<T: scale::Decode + Send> {
@@ -235,16 +359,33 @@ pub struct QueryArgs<T> {
     pub data: Vec<u8>,
     /// The value to be sent with the call.
     pub value: u128,
+    /// The call-specific overrides of the connection's defaults.
+    pub overrides: CallOverrides,
     /// A marker for the type to decode the result into.
     _return_type: PhantomData<T>,
 }
 
+#[cfg(feature = "drink")]
+impl<T> QueryArgs<T> {
+    /// Returns the same query, with its result decoded into `U` instead.
+    pub(crate) fn decoded_as<U>(self) -> QueryArgs<U> {
+        QueryArgs {
+            account_id: self.account_id,
+            data: self.data,
+            value: self.value,
+            overrides: self.overrides,
+            _return_type: PhantomData,
+        }
+    }
+}
+
 impl<T: scale::Decode + Send> From<ReadCall<T>> for QueryArgs<T> {
     fn from(value: ReadCall<T>) -> QueryArgs<T> {
         Self {
             account_id: value.account_id,
             data: value.data.clone(),
             value: value.value,
+            overrides: value.overrides,
             _return_type: Default::default(),
         }
     }
@@ -256,6 +397,19 @@ impl<T: scale::Decode + Send> From<ExecCall<T>> for QueryArgs<T> {
             account_id: value.account_id,
             data: value.data.clone(),
             value: value.value,
+            overrides: value.overrides,
+            _return_type: Default::default(),
+        }
+    }
+}
+
+impl<T: scale::Decode + Send> From<&ExecCall<T>> for QueryArgs<T> {
+    fn from(value: &ExecCall<T>) -> QueryArgs<T> {
+        Self {
+            account_id: value.account_id,
+            data: value.data.clone(),
+            value: value.value,
+            overrides: value.overrides,
             _return_type: Default::default(),
         }
     }
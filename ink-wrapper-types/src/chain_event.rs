@@ -0,0 +1,215 @@
+use ink_primitives::AccountId;
+use scale::Decode;
+
+use crate::ContractEvent;
+
+/// An event emitted by the runtime during a call, with the events of the contracts pallet and transfers decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainEvent {
+    /// An event of the contracts pallet.
+    Contracts(ContractsEvent),
+    /// A transfer of the native currency (`Balances::Transfer`), e.g. the value sent with a call.
+    Transfer {
+        from: AccountId,
+        to: AccountId,
+        amount: u128,
+    },
+    /// Any other event, named after its pallet and variant.
+    Other { pallet: String, variant: String },
+}
+
+/// An event of the contracts pallet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContractsEvent {
+    /// A contract was instantiated by `deployer`.
+    Instantiated {
+        deployer: AccountId,
+        contract: AccountId,
+    },
+    /// A contract was terminated, sending its remaining balance to `beneficiary`.
+    Terminated {
+        contract: AccountId,
+        beneficiary: AccountId,
+    },
+    /// Code was uploaded.
+    CodeStored {
+        code_hash: [u8; 32],
+        deposit_held: u128,
+        uploader: AccountId,
+    },
+    /// A contract emitted an event, also available in `ContractResult::events`.
+    ContractEmitted(ContractEvent),
+    /// Code was removed.
+    CodeRemoved {
+        code_hash: [u8; 32],
+        deposit_released: u128,
+        remover: AccountId,
+    },
+    /// The code of a contract was replaced, e.g. with `set_code_hash`.
+    ContractCodeUpdated {
+        contract: AccountId,
+        new_code_hash: [u8; 32],
+        old_code_hash: [u8; 32],
+    },
+    /// A contract was called, by an account or by root (`caller` is `None`).
+    Called {
+        caller: Option<AccountId>,
+        contract: AccountId,
+    },
+    /// A contract delegated a call to the code with the given hash.
+    DelegateCalled {
+        contract: AccountId,
+        code_hash: [u8; 32],
+    },
+    /// A storage deposit was transferred and held.
+    StorageDepositTransferredAndHeld {
+        from: AccountId,
+        to: AccountId,
+        amount: u128,
+    },
+    /// A held storage deposit was released and transferred.
+    StorageDepositTransferredAndReleased {
+        from: AccountId,
+        to: AccountId,
+        amount: u128,
+    },
+}
+
+impl ChainEvent {
+    /// Decodes an event from the names of its pallet and variant and its encoded fields.
+    ///
+    /// Account ids and hashes are expected to be 32 bytes long and balances to be `u128`. Events of other pallets, and
+    /// the ones whose fields don't match these expectations, are returned as `Other`.
+    pub(crate) fn decode(
+        pallet: &str,
+        variant: &str,
+        topics: Vec<[u8; 32]>,
+        fields: &[u8],
+    ) -> Self {
+        let fields = &mut &fields[..];
+        let event = match (pallet, variant) {
+            ("Contracts", _) => ContractsEvent::decode(variant, topics, fields)
+                .ok()
+                .flatten()
+                .map(Self::Contracts),
+            ("Balances", "Transfer") => {
+                <([u8; 32], [u8; 32], u128)>::decode(fields)
+                    .ok()
+                    .map(|(from, to, amount)| Self::Transfer {
+                        from: from.into(),
+                        to: to.into(),
+                        amount,
+                    })
+            }
+            _ => None,
+        };
+
+        event.unwrap_or_else(|| Self::Other {
+            pallet: pallet.to_string(),
+            variant: variant.to_string(),
+        })
+    }
+}
+
+impl ContractsEvent {
+    fn decode(
+        variant: &str,
+        topics: Vec<[u8; 32]>,
+        fields: &mut &[u8],
+    ) -> Result<Option<Self>, scale::Error> {
+        let event = match variant {
+            "Instantiated" => {
+                let (deployer, contract) = <([u8; 32], [u8; 32])>::decode(fields)?;
+                Self::Instantiated {
+                    deployer: deployer.into(),
+                    contract: contract.into(),
+                }
+            }
+            "Terminated" => {
+                let (contract, beneficiary) = <([u8; 32], [u8; 32])>::decode(fields)?;
+                Self::Terminated {
+                    contract: contract.into(),
+                    beneficiary: beneficiary.into(),
+                }
+            }
+            "CodeStored" => {
+                let (code_hash, deposit_held, uploader) =
+                    <([u8; 32], u128, [u8; 32])>::decode(fields)?;
+                Self::CodeStored {
+                    code_hash,
+                    deposit_held,
+                    uploader: uploader.into(),
+                }
+            }
+            "ContractEmitted" => {
+                let (contract, data) = <([u8; 32], Vec<u8>)>::decode(fields)?;
+                Self::ContractEmitted(ContractEvent {
+                    account_id: contract.into(),
+                    data,
+                    topics,
+                })
+            }
+            "CodeRemoved" => {
+                let (code_hash, deposit_released, remover) =
+                    <([u8; 32], u128, [u8; 32])>::decode(fields)?;
+                Self::CodeRemoved {
+                    code_hash,
+                    deposit_released,
+                    remover: remover.into(),
+                }
+            }
+            "ContractCodeUpdated" => {
+                let (contract, new_code_hash, old_code_hash) =
+                    <([u8; 32], [u8; 32], [u8; 32])>::decode(fields)?;
+                Self::ContractCodeUpdated {
+                    contract: contract.into(),
+                    new_code_hash,
+                    old_code_hash,
+                }
+            }
+            "Called" => {
+                let (caller, contract) = <(Origin, [u8; 32])>::decode(fields)?;
+                Self::Called {
+                    caller: match caller {
+                        Origin::Root => None,
+                        Origin::Signed(caller) => Some(caller.into()),
+                    },
+                    contract: contract.into(),
+                }
+            }
+            "DelegateCalled" => {
+                let (contract, code_hash) = <([u8; 32], [u8; 32])>::decode(fields)?;
+                Self::DelegateCalled {
+                    contract: contract.into(),
+                    code_hash,
+                }
+            }
+            "StorageDepositTransferredAndHeld" => {
+                let (from, to, amount) = <([u8; 32], [u8; 32], u128)>::decode(fields)?;
+                Self::StorageDepositTransferredAndHeld {
+                    from: from.into(),
+                    to: to.into(),
+                    amount,
+                }
+            }
+            "StorageDepositTransferredAndReleased" => {
+                let (from, to, amount) = <([u8; 32], [u8; 32], u128)>::decode(fields)?;
+                Self::StorageDepositTransferredAndReleased {
+                    from: from.into(),
+                    to: to.into(),
+                    amount,
+                }
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(event))
+    }
+}
+
+/// Mirrors `pallet_contracts::Origin`, the caller in `Called` events.
+#[derive(Decode)]
+enum Origin {
+    Root,
+    Signed([u8; 32]),
+}
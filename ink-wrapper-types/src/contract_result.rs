@@ -0,0 +1,151 @@
+use pallet_contracts_primitives::StorageDeposit;
+use sp_weights::Weight;
+
+use crate::{CallError, ChainEvent, ContractEvent, MessageOutput};
+
+/// The outcome of a contract call, as reported by the backend that executed it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractResult<R> {
+    pub gas_consumed: Weight,
+    pub gas_required: Weight,
+    pub result: R,
+    pub events: Vec<ContractEvent>,
+    /// All the events emitted by the runtime during the call, in order (e.g. instantiations, calls and transfers).
+    pub runtime_events: Vec<ChainEvent>,
+    pub reverted: bool,
+    pub debug_message: Vec<u8>,
+    pub storage_deposit: StorageDeposit<u128>,
+}
+
+/// The outcome of a code upload, as reported by the backend that executed it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UploadResult<Hash> {
+    pub code_hash: Hash,
+    /// All the events emitted by the runtime during the upload, e.g. `ContractsEvent::CodeStored` if the code wasn't on
+    /// the chain yet.
+    pub runtime_events: Vec<ChainEvent>,
+}
+
+pub type ContractInstantiateResult<AccountId> = ContractResult<AccountId>;
+
+pub type ContractExecResult<R> = ContractResult<R>;
+
+pub type ContractReadResult<R> = ContractResult<R>;
+
+impl<R> ContractResult<R> {
+    /// Returns the lines the contract printed to the debug buffer (e.g. with `ink::env::debug_println!`), decoding
+    /// invalid UTF-8 lossily.
+    pub fn debug_lines(&self) -> Vec<String> {
+        String::from_utf8_lossy(&self.debug_message)
+            .lines()
+            .map(ToString::to_string)
+            .collect()
+    }
+}
+
+impl<R: MessageOutput> ContractResult<R> {
+    /// Returns the value returned by the message, or an error if the message failed or the call reverted.
+    pub fn ok(self) -> Result<R::Value, CallError<R::Error>> {
+        let reverted = self.reverted;
+
+        match self.result.into_result() {
+            Ok(_) if reverted => Err(CallError::Reverted),
+            result => result,
+        }
+    }
+}
+
+/// Flattens the outcome of a call made with a backend, see [ContractResult::ok].
+pub trait CallResultExt<R: MessageOutput> {
+    fn outcome(self) -> Result<R::Value, CallError<R::Error>>;
+}
+
+impl<R, B> CallResultExt<R> for Result<ContractResult<R>, B>
+where
+    R: MessageOutput,
+    B: std::error::Error + Send + Sync + 'static,
+{
+    fn outcome(self) -> Result<R::Value, CallError<R::Error>> {
+        self.map_err(CallError::backend)?.ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ink_primitives::LangError;
+
+    use super::*;
+    use crate::{Flat, FlatResult};
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("connection lost")]
+    struct BackendError;
+
+    fn contract_result<R>(result: R, reverted: bool) -> ContractResult<R> {
+        ContractResult {
+            gas_consumed: Weight::zero(),
+            gas_required: Weight::zero(),
+            result,
+            events: vec![],
+            runtime_events: vec![],
+            reverted,
+            debug_message: vec![],
+            storage_deposit: StorageDeposit::Charge(0),
+        }
+    }
+
+    #[test]
+    fn test_ok_value() {
+        let result = contract_result(FlatResult::<u32, u8>(Ok(Ok(42))), false);
+        assert!(matches!(result.ok(), Ok(42)));
+
+        let result = contract_result(Flat(Ok(42u32)), false);
+        assert!(matches!(result.ok(), Ok(42)));
+    }
+
+    #[test]
+    fn test_lang_error() {
+        // ink! reverts calls that fail to dispatch, but the dispatch error says more than `Reverted`.
+        let result = contract_result(Flat::<u32>(Err(LangError::CouldNotReadInput.into())), true);
+        let error = result.ok().unwrap_err();
+
+        assert!(matches!(error, CallError::Lang(_)));
+        assert_eq!(
+            error.to_string(),
+            "Message dispatch failed: InkLangError(CouldNotReadInput)"
+        );
+    }
+
+    #[test]
+    fn test_contract_error() {
+        let result = contract_result(FlatResult::<u32, u8>(Ok(Err(7))), true);
+        let error = result.ok().unwrap_err();
+
+        assert!(matches!(error, CallError::Contract(7)));
+        assert_eq!(error.to_string(), "The contract returned an error: 7");
+    }
+
+    #[test]
+    fn test_reverted() {
+        let result = contract_result(FlatResult::<u32, u8>(Ok(Ok(42))), true);
+        assert!(matches!(result.ok(), Err(CallError::Reverted)));
+
+        let result = contract_result(Flat(Ok(42u32)), true);
+        assert!(matches!(result.ok(), Err(CallError::Reverted)));
+    }
+
+    #[test]
+    fn test_backend_error() {
+        let call: Result<ContractResult<Flat<u32>>, _> = Err(BackendError);
+        let error = call.outcome().unwrap_err();
+
+        assert!(matches!(error, CallError::Backend(_)));
+        assert_eq!(error.to_string(), "Backend error: connection lost");
+    }
+
+    #[test]
+    fn test_outcome_of_successful_call() {
+        let call: Result<_, BackendError> = Ok(contract_result(Flat(Ok(42u32)), false));
+        assert!(matches!(call.outcome(), Ok(42)));
+    }
+}
@@ -0,0 +1,178 @@
+/// A `DispatchError` returned by the chain for a failed upload, instantiation or call, with the errors of the contracts
+/// pallet decoded.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DispatchFailure {
+    /// An error of the contracts pallet.
+    #[error("{0}")]
+    Contracts(ContractsError),
+    /// Any other error, described as well as the backend can (e.g. `Balances::InsufficientBalance` or `BadOrigin`).
+    #[error("{0}")]
+    Other(String),
+}
+
+/// An error of the contracts pallet.
+///
+/// Errors are matched by the name of the variant in the chain's `pallet_contracts::Error`, so versions of the pallet
+/// with different variants are supported as well. Variants not listed here end up in `Unknown`.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ContractsError {
+    #[error("InvalidSchedule: invalid schedule supplied")]
+    InvalidSchedule,
+    #[error("InvalidCallFlags: invalid combination of flags supplied to `seal_call` or `seal_delegate_call`")]
+    InvalidCallFlags,
+    #[error("OutOfGas: the executed contract exhausted its gas limit")]
+    OutOfGas,
+    #[error(
+        "OutputBufferTooSmall: the output buffer supplied to a contract API call was too small"
+    )]
+    OutputBufferTooSmall,
+    #[error("TransferFailed: the transfer failed, probably because of insufficient free balance")]
+    TransferFailed,
+    #[error("MaxCallDepthReached: the calling depth reached the limit")]
+    MaxCallDepthReached,
+    #[error("ContractNotFound: no contract was found at the specified address")]
+    ContractNotFound,
+    #[error("CodeTooLarge: the code exceeds the size limit")]
+    CodeTooLarge,
+    #[error("CodeNotFound: no code could be found at the supplied code hash")]
+    CodeNotFound,
+    #[error("CodeInfoNotFound: no code info could be found at the supplied code hash")]
+    CodeInfoNotFound,
+    #[error(
+        "OutOfBounds: a buffer outside of sandbox memory was passed to a contract API function"
+    )]
+    OutOfBounds,
+    #[error("DecodingFailed: input passed to a contract API function failed to decode")]
+    DecodingFailed,
+    #[error("ContractTrapped: the contract trapped during execution")]
+    ContractTrapped,
+    #[error("ValueTooLarge: the maximum value size was exceeded")]
+    ValueTooLarge,
+    #[error("TerminatedWhileReentrant: the contract was terminated while on the call stack")]
+    TerminatedWhileReentrant,
+    #[error("InputForwarded: the input was forwarded by `seal_call` and is no longer available")]
+    InputForwarded,
+    #[error("RandomSubjectTooLong: the subject passed to `seal_random` exceeds the limit")]
+    RandomSubjectTooLong,
+    #[error("TooManyTopics: the event has too many topics")]
+    TooManyTopics,
+    #[error("NoChainExtension: the chain does not provide a chain extension")]
+    NoChainExtension,
+    #[error("DuplicateContract: a contract with the same address already exists")]
+    DuplicateContract,
+    #[error("TerminatedInConstructor: the contract terminated itself in its constructor")]
+    TerminatedInConstructor,
+    #[error(
+        "ReentranceDenied: the call tried to reenter a contract that is flagged as non-reentrant"
+    )]
+    ReentranceDenied,
+    #[error("StorageDepositNotEnoughFunds: the origin doesn't have enough balance to pay the storage deposit")]
+    StorageDepositNotEnoughFunds,
+    #[error("StorageDepositLimitExhausted: more storage was created than allowed by the storage deposit limit")]
+    StorageDepositLimitExhausted,
+    #[error("CodeInUse: the code is still in use by at least one contract")]
+    CodeInUse,
+    #[error("ContractReverted: the contract reverted its storage changes")]
+    ContractReverted,
+    #[error("CodeRejected: the code was found to be invalid during validation")]
+    CodeRejected,
+    #[error("Indeterministic: indeterministic code was used where it's not permitted")]
+    Indeterministic,
+    #[error("MigrationInProgress: a migration of the pallet needs to complete first")]
+    MigrationInProgress,
+    #[error("NoMigrationPerformed: no migration was performed")]
+    NoMigrationPerformed,
+    #[error("MaxDelegateDependenciesReached: the contract has reached its maximum number of delegate dependencies")]
+    MaxDelegateDependenciesReached,
+    #[error("DelegateDependencyNotFound: the delegate dependency was not found")]
+    DelegateDependencyNotFound,
+    #[error(
+        "DelegateDependencyAlreadyExists: the contract already depends on the delegate dependency"
+    )]
+    DelegateDependencyAlreadyExists,
+    #[error("CannotAddSelfAsDelegateDependency: the contract can't depend on its own code")]
+    CannotAddSelfAsDelegateDependency,
+    /// An error this version of `ink-wrapper-types` doesn't know about.
+    #[error("{0}: unknown error of the contracts pallet")]
+    Unknown(String),
+}
+
+impl ContractsError {
+    /// Returns the error with the given variant name.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "InvalidSchedule" => Self::InvalidSchedule,
+            "InvalidCallFlags" => Self::InvalidCallFlags,
+            "OutOfGas" => Self::OutOfGas,
+            "OutputBufferTooSmall" => Self::OutputBufferTooSmall,
+            "TransferFailed" => Self::TransferFailed,
+            "MaxCallDepthReached" => Self::MaxCallDepthReached,
+            "ContractNotFound" => Self::ContractNotFound,
+            "CodeTooLarge" => Self::CodeTooLarge,
+            "CodeNotFound" => Self::CodeNotFound,
+            "CodeInfoNotFound" => Self::CodeInfoNotFound,
+            "OutOfBounds" => Self::OutOfBounds,
+            "DecodingFailed" => Self::DecodingFailed,
+            "ContractTrapped" => Self::ContractTrapped,
+            "ValueTooLarge" => Self::ValueTooLarge,
+            "TerminatedWhileReentrant" => Self::TerminatedWhileReentrant,
+            "InputForwarded" => Self::InputForwarded,
+            "RandomSubjectTooLong" => Self::RandomSubjectTooLong,
+            "TooManyTopics" => Self::TooManyTopics,
+            "NoChainExtension" => Self::NoChainExtension,
+            "DuplicateContract" => Self::DuplicateContract,
+            "TerminatedInConstructor" => Self::TerminatedInConstructor,
+            "ReentranceDenied" => Self::ReentranceDenied,
+            "StorageDepositNotEnoughFunds" => Self::StorageDepositNotEnoughFunds,
+            "StorageDepositLimitExhausted" => Self::StorageDepositLimitExhausted,
+            "CodeInUse" => Self::CodeInUse,
+            "ContractReverted" => Self::ContractReverted,
+            "CodeRejected" => Self::CodeRejected,
+            "Indeterministic" => Self::Indeterministic,
+            "MigrationInProgress" => Self::MigrationInProgress,
+            "NoMigrationPerformed" => Self::NoMigrationPerformed,
+            "MaxDelegateDependenciesReached" => Self::MaxDelegateDependenciesReached,
+            "DelegateDependencyNotFound" => Self::DelegateDependencyNotFound,
+            "DelegateDependencyAlreadyExists" => Self::DelegateDependencyAlreadyExists,
+            "CannotAddSelfAsDelegateDependency" => Self::CannotAddSelfAsDelegateDependency,
+            name => Self::Unknown(name.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_errors() {
+        assert_eq!(
+            ContractsError::from_name("ContractTrapped"),
+            ContractsError::ContractTrapped
+        );
+        assert_eq!(
+            ContractsError::ContractTrapped.to_string(),
+            "ContractTrapped: the contract trapped during execution"
+        );
+
+        assert_eq!(
+            ContractsError::from_name("OutOfGas"),
+            ContractsError::OutOfGas
+        );
+        assert_eq!(
+            DispatchFailure::Contracts(ContractsError::OutOfGas).to_string(),
+            "OutOfGas: the executed contract exhausted its gas limit"
+        );
+    }
+
+    #[test]
+    fn test_unknown_error() {
+        let error = ContractsError::from_name("SomethingNew");
+
+        assert_eq!(error, ContractsError::Unknown("SomethingNew".to_string()));
+        assert_eq!(
+            error.to_string(),
+            "SomethingNew: unknown error of the contracts pallet"
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use std::{
+    cell::{RefCell, RefMut},
+    marker::PhantomData,
+};
+
+use super::*;
+use crate::LocalAsyncConnection;
+
+/// Drives a synchronous `Connection` (e.g. a drink `Session`) through the `LocalAsyncConnection` trait.
+///
+/// Useful for testing code written against `LocalAsyncConnection` without a live chain. The calls complete immediately and
+/// run one at a time - a call made while another one is borrowing the connection panics.
+pub struct AsyncAdapter<C, R> {
+    connection: RefCell<C>,
+    _runtime: PhantomData<R>,
+}
+
+impl<C, R> AsyncAdapter<C, R> {
+    pub fn new(connection: C) -> Self {
+        Self {
+            connection: RefCell::new(connection),
+            _runtime: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped connection.
+    pub fn into_inner(self) -> C {
+        self.connection.into_inner()
+    }
+
+    /// Borrows the wrapped connection, e.g. to inspect the state of a drink session between calls.
+    pub fn borrow_mut(&self) -> RefMut<'_, C> {
+        self.connection.borrow_mut()
+    }
+}
+
+impl<C, R> From<C> for AsyncAdapter<C, R> {
+    fn from(connection: C) -> Self {
+        Self::new(connection)
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl<C: Connection<R>, R: frame_system::Config> LocalAsyncConnection for AsyncAdapter<C, R> {
+    type AccountId = R::AccountId;
+    type Hash = HashFor<R>;
+    type Error = Error;
+
+    async fn upload_code(&self, call: UploadCall) -> Result<UploadResult<HashFor<R>>, Error> {
+        self.borrow_mut().upload_code(call)
+    }
+
+    async fn instantiate<T: Send>(
+        &self,
+        call: InstantiateCall<T>,
+    ) -> Result<ContractInstantiateResult<R::AccountId>, Error> {
+        self.borrow_mut().instantiate(call)
+    }
+
+    async fn execute<T: scale::Decode + Send + std::fmt::Debug>(
+        &self,
+        call: ExecCall<T>,
+    ) -> Result<ContractExecResult<T>, Error> {
+        self.borrow_mut().execute(call)
+    }
+
+    async fn query<T: scale::Decode + Send + std::fmt::Debug>(
+        &self,
+        call: impl Into<QueryArgs<T>>,
+    ) -> Result<ContractReadResult<T>, Error> {
+        self.borrow_mut().query(call)
+    }
+}
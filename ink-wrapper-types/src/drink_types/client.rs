@@ -1,60 +1,88 @@
 use drink::{
-    frame_system, pallet_contracts,
-    runtime::{HashFor, MinimalRuntime},
+    frame_support::traits::{fungible::Inspect, PalletInfo},
+    pallet_contracts,
+    runtime::{AccountIdFor, HashFor, RuntimeWithContracts},
     session::Session,
-    Weight,
+    DispatchError, EventRecordOf, Sandbox, Weight,
 };
+use pallet_contracts_primitives::StorageDeposit;
+use scale_info::{Type, TypeDef, TypeInfo};
 
 use super::*;
-use crate::utils::ToAccountId;
+use crate::{
+    utils::ToAccountId, CallOverrides, ChainEvent, ContractEvent, ContractsError, DispatchFailure,
+};
 
-type MinimalRuntimeAccount = <MinimalRuntime as frame_system::Config>::AccountId;
+/// The balance type used by the contracts pallet of the runtime.
+pub type BalanceOf<R> =
+    <<R as pallet_contracts::Config>::Currency as Inspect<AccountIdFor<R>>>::Balance;
 
-// NOTE: This needs to be fixed at `MinimalRuntime` as `ink-wrapper` uses `u128` to represent
-// token balances. `R::Balance` is a trait which does not provide conversion from `u128`.
-// `MinimalRuntime` has its `Balance` type fixed to `u128`.
-impl Connection<MinimalRuntime> for Session<MinimalRuntime> {
-    fn upload_code(&mut self, call: UploadCall) -> Result<HashFor<MinimalRuntime>, Error> {
+// `ink-wrapper` represents token balances as `u128`, so the runtime's balance type needs to convert from and into it.
+// Account ids are passed around as `[u8; 32]` and the contract events are extracted from the runtime events.
+impl<R> Connection<R> for Session<R>
+where
+    R: RuntimeWithContracts,
+    BalanceOf<R>: From<u128> + Into<u128>,
+    AccountIdFor<R>: From<[u8; 32]> + AsRef<[u8; 32]>,
+    HashFor<R>: Into<[u8; 32]>,
+    <R as frame_system::Config>::RuntimeEvent: TryInto<pallet_contracts::Event<R>>,
+{
+    fn upload_code(&mut self, call: UploadCall) -> Result<UploadResult<HashFor<R>>, Error> {
+        // Reset events to make sure we don't have any events from previous calls.
+        self.sandbox().reset_events();
         let code_hash = self.upload(call.wasm).map_err(|_| Error::UploadFailed)?;
         if code_hash.as_ref() != call.expected_code_hash {
             return Err(Error::CodeHashMismatch);
         }
-        Ok(code_hash)
+
+        let events = Some(self.sandbox().events());
+        Ok(UploadResult {
+            code_hash,
+            runtime_events: extract_runtime_events::<R>(&events),
+        })
     }
 
     fn instantiate<T: Send>(
         &mut self,
         call: InstantiateCall<T>,
-    ) -> Result<ContractInstantiateResult<MinimalRuntimeAccount>, Error> {
-        let actor = self.get_actor();
-        let gas_limit = self.get_gas_limit();
+    ) -> Result<ContractInstantiateResult<AccountIdFor<R>>, Error> {
+        let (actor, gas_limit) = actor_and_gas_limit(self, &call.overrides);
 
+        // Reset events to make sure we don't have any events from previous calls.
+        self.sandbox().reset_events();
         let instantiate_contract_result = self.sandbox().instantiate_contract(
             call.code_hash.to_vec(),
-            call.value,
+            call.value.into(),
             call.data,
             call.salt,
             actor,
             gas_limit,
-            None,
+            call.overrides.storage_deposit_limit.map(Into::into),
         );
 
         let contract_address = match &instantiate_contract_result.result {
-            Ok(exec_result) if exec_result.result.did_revert() => Err(Error::DeploymentReverted),
-            Err(err) => Err(Error::DeploymentFailed(*err)),
+            Ok(exec_result) if exec_result.result.did_revert() => Err(Error::DeploymentReverted {
+                debug_message: String::from_utf8_lossy(&instantiate_contract_result.debug_message)
+                    .into_owned(),
+            }),
+            Err(err) => Err(Error::DeploymentFailed(dispatch_failure::<R>(*err))),
             Ok(exec_result) => Ok(exec_result.account_id.clone()),
         }?;
 
-        let events = extract_events(&instantiate_contract_result.events);
+        let events = extract_events::<R>(&instantiate_contract_result.events);
+        let runtime_events = extract_runtime_events::<R>(&instantiate_contract_result.events);
 
         Ok(ContractInstantiateResult {
             gas_consumed: instantiate_contract_result.gas_consumed,
             gas_required: instantiate_contract_result.gas_required,
             result: contract_address,
             events,
+            runtime_events,
             reverted: false,
             debug_message: instantiate_contract_result.debug_message,
-            storage_deposit: instantiate_contract_result.storage_deposit,
+            storage_deposit: convert_storage_deposit::<R>(
+                instantiate_contract_result.storage_deposit,
+            ),
         })
     }
 
@@ -62,13 +90,13 @@ impl Connection<MinimalRuntime> for Session<MinimalRuntime> {
         &mut self,
         call: ExecCall<T>,
     ) -> Result<ContractExecResult<T>, Error> {
-        let actor = self.get_actor();
-        let gas_limit = self.get_gas_limit();
+        let (actor, gas_limit) = actor_and_gas_limit(self, &call.overrides);
         let contract_address = (*AsRef::<[u8; 32]>::as_ref(&call.account_id)).into();
 
         call_contract(
             actor,
             gas_limit,
+            call.overrides.storage_deposit_limit,
             self.sandbox(),
             contract_address,
             call.value,
@@ -81,34 +109,70 @@ impl Connection<MinimalRuntime> for Session<MinimalRuntime> {
         call: impl Into<QueryArgs<T>>,
     ) -> Result<ContractReadResult<T>, Error> {
         let args = call.into();
-        let actor = self.get_actor();
-        let gas_limit = self.get_gas_limit();
+        let (actor, gas_limit) = actor_and_gas_limit(self, &args.overrides);
         let contract_address = (*AsRef::<[u8; 32]>::as_ref(&args.account_id)).into();
 
         self.sandbox().dry_run(|sandbox| {
-            call_contract(actor, gas_limit, sandbox, contract_address, 0, args.data)
+            call_contract(
+                actor,
+                gas_limit,
+                args.overrides.storage_deposit_limit,
+                sandbox,
+                contract_address,
+                args.value,
+                args.data,
+            )
         })
     }
 }
 
-fn call_contract<T: scale::Decode + Send + std::fmt::Debug>(
-    actor: MinimalRuntimeAccount,
+/// Returns the caller and gas limit of a call - the ones set on the call, or the session's defaults.
+fn actor_and_gas_limit<R>(
+    session: &Session<R>,
+    overrides: &CallOverrides,
+) -> (AccountIdFor<R>, Weight)
+where
+    R: RuntimeWithContracts,
+    AccountIdFor<R>: From<[u8; 32]>,
+{
+    let actor = match overrides.caller {
+        Some(caller) => (*AsRef::<[u8; 32]>::as_ref(&caller)).into(),
+        None => session.get_actor(),
+    };
+    let gas_limit = overrides
+        .gas_limit
+        .unwrap_or_else(|| session.get_gas_limit());
+
+    (actor, gas_limit)
+}
+
+fn call_contract<T, R>(
+    actor: AccountIdFor<R>,
     gas_limit: Weight,
-    sandbox: &mut drink::Sandbox<MinimalRuntime>,
-    address: MinimalRuntimeAccount,
+    storage_deposit_limit: Option<u128>,
+    sandbox: &mut Sandbox<R>,
+    address: AccountIdFor<R>,
     value: u128,
     data: Vec<u8>,
-) -> Result<ContractResult<T>, Error> {
+) -> Result<ContractResult<T>, Error>
+where
+    T: scale::Decode + Send + std::fmt::Debug,
+    R: RuntimeWithContracts,
+    BalanceOf<R>: From<u128> + Into<u128>,
+    AccountIdFor<R>: AsRef<[u8; 32]>,
+    HashFor<R>: Into<[u8; 32]>,
+    <R as frame_system::Config>::RuntimeEvent: TryInto<pallet_contracts::Event<R>>,
+{
     // Reset events to make sure we don't have any events from previous calls.
     sandbox.reset_events();
 
     let result = sandbox.call_contract(
         address,
-        value,
+        value.into(),
         data,
         actor,
         gas_limit,
-        None,
+        storage_deposit_limit.map(Into::into),
         pallet_contracts::Determinism::Enforced,
     );
 
@@ -122,44 +186,164 @@ fn call_contract<T: scale::Decode + Send + std::fmt::Debug>(
                 ))
             })
         }
-        Err(err) => Err(Error::CallFailed(*err)),
+        Err(err) => Err(Error::CallFailed(dispatch_failure::<R>(*err))),
     }?;
 
-    let events = extract_events(&result.events);
+    let events = extract_events::<R>(&result.events);
+    let runtime_events = extract_runtime_events::<R>(&result.events);
 
     Ok(ContractResult {
         gas_consumed: result.gas_consumed,
         gas_required: result.gas_required,
         result: message_result,
         events,
+        runtime_events,
         reverted: result
             .result
             .expect("If `result.result` was `err`, we should have returned `Err` from the whole function.")
             .did_revert(),
         debug_message: result.debug_message,
-        storage_deposit: result.storage_deposit,
+        storage_deposit: convert_storage_deposit::<R>(result.storage_deposit),
     })
 }
 
-fn extract_events(
-    events: &Option<Vec<drink::EventRecordOf<MinimalRuntime>>>,
-) -> Vec<ContractEvent> {
+fn extract_events<R>(events: &Option<Vec<EventRecordOf<R>>>) -> Vec<ContractEvent>
+where
+    R: RuntimeWithContracts,
+    AccountIdFor<R>: AsRef<[u8; 32]>,
+    HashFor<R>: Into<[u8; 32]>,
+    <R as frame_system::Config>::RuntimeEvent: TryInto<pallet_contracts::Event<R>>,
+{
     events
         .clone()
         .unwrap_or_default()
         .into_iter()
-        .filter_map(|event| match event.event {
-            drink::runtime::minimal::RuntimeEvent::Contracts(events) => Some(events),
+        .filter_map(|record| match record.event.try_into().ok()? {
+            pallet_contracts::Event::ContractEmitted { contract, data } => Some(ContractEvent {
+                account_id: contract.to_account_id(),
+                data,
+                topics: record.topics.into_iter().map(Into::into).collect(),
+            }),
             _ => None,
         })
-        .filter_map(|event| match event {
-            pallet_contracts::pallet::Event::ContractEmitted { contract, data } => {
-                Some(ContractEvent {
-                    account_id: contract.to_account_id(),
-                    data,
-                })
-            }
-            _ => None,
+        .collect()
+}
+
+/// Decodes all the runtime events with `ChainEvent::decode`.
+///
+/// The encoding of a runtime event starts with the indices of the pallet and of the event within the pallet, which are
+/// looked up in the type info of the runtime's event type to find their names.
+fn extract_runtime_events<R>(events: &Option<Vec<EventRecordOf<R>>>) -> Vec<ChainEvent>
+where
+    R: RuntimeWithContracts,
+    HashFor<R>: Into<[u8; 32]>,
+{
+    events
+        .iter()
+        .flatten()
+        .filter_map(|record| {
+            let encoded = scale::Encode::encode(&record.event);
+            let (pallet, variant) =
+                event_names::<<R as frame_system::Config>::RuntimeEvent>(&encoded)?;
+            let topics = record.topics.iter().map(|topic| (*topic).into()).collect();
+
+            Some(ChainEvent::decode(pallet, variant, topics, &encoded[2..]))
         })
         .collect()
 }
+
+/// Returns the names of the pallet and the event the encoded runtime event belongs to.
+fn event_names<E: TypeInfo>(encoded: &[u8]) -> Option<(&'static str, &'static str)> {
+    let variant = |type_info: Type, index: u8| match type_info.type_def {
+        TypeDef::Variant(variants) => variants.variants.into_iter().find(|v| v.index == index),
+        _ => None,
+    };
+
+    let pallet = variant(E::type_info(), *encoded.first()?)?;
+    let event = variant(pallet.fields.first()?.ty.type_info(), *encoded.get(1)?)?;
+
+    Some((pallet.name, event.name))
+}
+
+/// Decodes the errors of the runtime's contracts pallet by their index in `pallet_contracts::Error`.
+fn dispatch_failure<R: RuntimeWithContracts>(error: DispatchError) -> DispatchFailure {
+    let contracts_index =
+        <R as frame_system::Config>::PalletInfo::index::<pallet_contracts::Pallet<R>>();
+
+    match error {
+        DispatchError::Module(module) if Some(module.index as usize) == contracts_index => {
+            let error =
+                <pallet_contracts::Error<R> as scale::Decode>::decode(&mut &module.error[..]);
+            match error {
+                Ok(error) => DispatchFailure::Contracts(ContractsError::from_name(error.into())),
+                Err(_) => DispatchFailure::Other(format!("{module:?}")),
+            }
+        }
+        DispatchError::Module(module) => match module.message {
+            Some(message) => DispatchFailure::Other(message.to_string()),
+            None => DispatchFailure::Other(format!("{module:?}")),
+        },
+        error => DispatchFailure::Other(format!("{error:?}")),
+    }
+}
+
+fn convert_storage_deposit<R>(deposit: StorageDeposit<BalanceOf<R>>) -> StorageDeposit<u128>
+where
+    R: RuntimeWithContracts,
+    BalanceOf<R>: Into<u128>,
+{
+    match deposit {
+        StorageDeposit::Refund(amount) => StorageDeposit::Refund(amount.into()),
+        StorageDeposit::Charge(amount) => StorageDeposit::Charge(amount.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use drink::{frame_support::sp_runtime::ModuleError, runtime::MinimalRuntime};
+
+    use super::*;
+
+    /// A module error of the pallet at `index`, with the variant at `error` in the pallet's `Error`.
+    fn module_error(index: u8, error: u8, message: Option<&'static str>) -> DispatchError {
+        DispatchError::Module(ModuleError {
+            index,
+            error: [error, 0, 0, 0],
+            message,
+        })
+    }
+
+    #[test]
+    fn test_contracts_errors() {
+        // `Contracts` is the fourth pallet of `MinimalRuntime`.
+        let error = dispatch_failure::<MinimalRuntime>(module_error(3, 12, None));
+        assert_eq!(
+            error,
+            DispatchFailure::Contracts(ContractsError::ContractTrapped)
+        );
+        assert_eq!(
+            error.to_string(),
+            "ContractTrapped: the contract trapped during execution"
+        );
+
+        let error = dispatch_failure::<MinimalRuntime>(module_error(3, 2, None));
+        assert_eq!(error, DispatchFailure::Contracts(ContractsError::OutOfGas));
+    }
+
+    #[test]
+    fn test_other_errors() {
+        // There's no variant at this index of `pallet_contracts::Error`.
+        let error = dispatch_failure::<MinimalRuntime>(module_error(3, 200, None));
+        assert!(matches!(error, DispatchFailure::Other(_)));
+
+        let error =
+            dispatch_failure::<MinimalRuntime>(module_error(1, 2, Some("InsufficientBalance")));
+        assert_eq!(
+            error,
+            DispatchFailure::Other("InsufficientBalance".to_string())
+        );
+
+        let error = dispatch_failure::<MinimalRuntime>(DispatchError::BadOrigin);
+        assert_eq!(error.to_string(), "BadOrigin");
+    }
+}
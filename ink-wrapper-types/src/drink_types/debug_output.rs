@@ -0,0 +1,113 @@
+use super::*;
+
+/// Where `DebugOutputEcho` writes the debug output of contracts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugOutput {
+    /// Print the lines to the standard error, where `cargo test` captures them like the rest of the test's output.
+    Stderr,
+    /// Emit the lines as `DEBUG` events with the `ink_wrapper::contract` target. Every call runs inside a
+    /// `contract_call` span, so whatever the runtime traces during the call is attached to it too.
+    Tracing,
+}
+
+/// Adds `with_debug_output` to drink connections.
+pub trait DebugOutputExt<R: frame_system::Config>: Connection<R> + Sized {
+    /// Echoes the debug output of every call, see `DebugOutputEcho`.
+    fn with_debug_output(self, output: DebugOutput) -> DebugOutputEcho<Self> {
+        DebugOutputEcho::new(self, output)
+    }
+}
+
+impl<R: frame_system::Config, C: Connection<R>> DebugOutputExt<R> for C {}
+
+/// A connection that echoes what contracts print to the debug buffer (e.g. with `ink::env::debug_println!`) during
+/// `instantiate`, `execute` and `query`, so it shows up in the output of tests.
+///
+/// The output is echoed before the result is decoded, so it's not lost when the call returns something unexpected, and
+/// calls that fail with an error carrying a debug message (see `Error::debug_message`) have it echoed too. Calls
+/// rejected by the contracts pallet don't return their debug output, so there's nothing to echo for them.
+pub struct DebugOutputEcho<C> {
+    connection: C,
+    output: DebugOutput,
+}
+
+impl<C> DebugOutputEcho<C> {
+    pub fn new(connection: C, output: DebugOutput) -> Self {
+        Self { connection, output }
+    }
+
+    fn echo(&self, call: &str, debug_message: &str) {
+        if debug_message.is_empty() {
+            return;
+        }
+
+        match self.output {
+            DebugOutput::Stderr => {
+                for line in debug_message.lines() {
+                    eprintln!("[{call}] {line}");
+                }
+            }
+            DebugOutput::Tracing => {
+                for line in debug_message.lines() {
+                    tracing::debug!(target: "ink_wrapper::contract", "{line}");
+                }
+            }
+        }
+    }
+
+    /// Enters the span of a call in `Tracing` mode, to be held until its output is echoed.
+    fn span(&self, call: &str) -> Option<tracing::span::EnteredSpan> {
+        match self.output {
+            DebugOutput::Stderr => None,
+            DebugOutput::Tracing => Some(tracing::debug_span!("contract_call", call).entered()),
+        }
+    }
+
+    /// Echoes the debug output of a call, whether it succeeded or not.
+    fn echo_result<T>(&self, call: &str, result: &Result<ContractResult<T>, Error>) {
+        match result {
+            Ok(result) => self.echo(call, &String::from_utf8_lossy(&result.debug_message)),
+            Err(err) => self.echo(call, err.debug_message().unwrap_or_default()),
+        }
+    }
+}
+
+connection_wrapper!(DebugOutputEcho);
+
+impl<C: Connection<R>, R: frame_system::Config> Connection<R> for DebugOutputEcho<C> {
+    fn upload_code(&mut self, call: UploadCall) -> Result<UploadResult<HashFor<R>>, Error> {
+        self.connection.upload_code(call)
+    }
+
+    fn instantiate<T: Send>(
+        &mut self,
+        call: InstantiateCall<T>,
+    ) -> Result<ContractInstantiateResult<R::AccountId>, Error> {
+        let _span = self.span("instantiate");
+        let result = self.connection.instantiate(call);
+        self.echo_result("instantiate", &result);
+        result
+    }
+
+    fn execute<T: scale::Decode + Send + std::fmt::Debug>(
+        &mut self,
+        call: ExecCall<T>,
+    ) -> Result<ContractExecResult<T>, Error> {
+        let _span = self.span("execute");
+        let result = self.connection.execute(call.decoded_as::<WithData<T>>());
+        self.echo_result("execute", &result);
+        decode_result(result?)
+    }
+
+    fn query<T: scale::Decode + Send + std::fmt::Debug>(
+        &mut self,
+        call: impl Into<QueryArgs<T>>,
+    ) -> Result<ContractReadResult<T>, Error> {
+        let _span = self.span("query");
+        let result = self
+            .connection
+            .query(call.into().decoded_as::<WithData<T>>());
+        self.echo_result("query", &result);
+        decode_result(result?)
+    }
+}
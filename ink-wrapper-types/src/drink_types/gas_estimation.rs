@@ -0,0 +1,88 @@
+use drink::Weight;
+
+use super::*;
+
+/// Adds `with_gas_estimation` to drink connections.
+pub trait GasEstimationExt<R: frame_system::Config>: Connection<R> + Sized {
+    /// Makes `execute` estimate the gas limit of calls that don't set one, see `GasEstimation`.
+    fn with_gas_estimation(self, margin_percent: u64) -> GasEstimation<Self> {
+        GasEstimation::new(self, margin_percent)
+    }
+}
+
+impl<R: frame_system::Config, C: Connection<R>> GasEstimationExt<R> for C {}
+
+/// A connection that dry-runs calls before executing them and uses the gas required by the dry run, increased by
+/// `margin_percent`, as the gas limit - the way cargo-contract and polkadot.js do it.
+///
+/// Only `execute` is affected, and only for calls without an explicit gas limit.
+pub struct GasEstimation<C> {
+    connection: C,
+    margin_percent: u64,
+}
+
+impl<C> GasEstimation<C> {
+    pub fn new(connection: C, margin_percent: u64) -> Self {
+        Self {
+            connection,
+            margin_percent,
+        }
+    }
+
+    /// Dry-runs the call and returns the gas limit `execute` would use for it.
+    pub fn estimate_gas<R, T>(&mut self, call: &ExecCall<T>) -> Result<Weight, Error>
+    where
+        C: Connection<R>,
+        R: frame_system::Config,
+        T: scale::Decode + Send + std::fmt::Debug,
+    {
+        let dry_run = self.connection.query(call)?;
+        Ok(add_margin(dry_run.gas_required, self.margin_percent))
+    }
+}
+
+connection_wrapper!(GasEstimation);
+
+impl<C: Connection<R>, R: frame_system::Config> Connection<R> for GasEstimation<C> {
+    fn upload_code(&mut self, call: UploadCall) -> Result<UploadResult<HashFor<R>>, Error> {
+        self.connection.upload_code(call)
+    }
+
+    fn instantiate<T: Send>(
+        &mut self,
+        call: InstantiateCall<T>,
+    ) -> Result<ContractInstantiateResult<R::AccountId>, Error> {
+        self.connection.instantiate(call)
+    }
+
+    fn execute<T: scale::Decode + Send + std::fmt::Debug>(
+        &mut self,
+        call: ExecCall<T>,
+    ) -> Result<ContractExecResult<T>, Error> {
+        let call = match call.overrides.gas_limit {
+            Some(_) => call,
+            None => {
+                let gas_limit = self.estimate_gas(&call)?;
+                call.with_gas_limit(gas_limit)
+            }
+        };
+
+        self.connection.execute(call)
+    }
+
+    fn query<T: scale::Decode + Send + std::fmt::Debug>(
+        &mut self,
+        call: impl Into<QueryArgs<T>>,
+    ) -> Result<ContractReadResult<T>, Error> {
+        self.connection.query(call)
+    }
+}
+
+fn add_margin(weight: Weight, margin_percent: u64) -> Weight {
+    let scale = |value: u64| {
+        let scaled = value as u128 * (100 + margin_percent as u128) / 100;
+        scaled.try_into().unwrap_or(u64::MAX)
+    };
+
+    Weight::from_parts(scale(weight.ref_time()), scale(weight.proof_size()))
+}
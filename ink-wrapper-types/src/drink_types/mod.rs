@@ -1,10 +1,45 @@
+/// Implements `into_inner`, `Deref` and `DerefMut` for a wrapper of a connection stored in its `connection` field.
+macro_rules! connection_wrapper {
+    ($wrapper:ident) => {
+        impl<C> $wrapper<C> {
+            /// Returns the wrapped connection.
+            pub fn into_inner(self) -> C {
+                self.connection
+            }
+        }
+
+        /// Gives access to the wrapped connection, e.g. to change the actor of a session.
+        impl<C> std::ops::Deref for $wrapper<C> {
+            type Target = C;
+
+            fn deref(&self) -> &C {
+                &self.connection
+            }
+        }
+
+        impl<C> std::ops::DerefMut for $wrapper<C> {
+            fn deref_mut(&mut self) -> &mut C {
+                &mut self.connection
+            }
+        }
+    };
+}
+
+mod async_adapter;
 mod client;
+mod debug_output;
+mod gas_estimation;
+mod strict_execution;
 
-use crate::{ContractEvent, ExecCall, InstantiateCall, QueryArgs, UploadCall};
+pub use crate::contract_result::*;
+use crate::{DispatchFailure, ExecCall, InstantiateCall, QueryArgs, UploadCall};
+pub use async_adapter::*;
 pub use client::*;
+pub use debug_output::*;
+pub use gas_estimation::*;
+pub use strict_execution::*;
 
-use drink::{frame_system, runtime::HashFor, DispatchError, Weight};
-use pallet_contracts_primitives::StorageDeposit;
+use drink::{frame_system, runtime::HashFor};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -14,16 +49,61 @@ pub enum Error {
     UploadFailed,
     #[error("Code hash mismatch")]
     CodeHashMismatch,
-    #[error("Deployment reverted")]
-    DeploymentReverted,
-    #[error("Deployment failed: {0:?}")]
-    DeploymentFailed(DispatchError),
-    #[error("Contract call failed: {0:?}")]
-    CallFailed(DispatchError),
+    #[error("Deployment reverted{}", debug_suffix(debug_message))]
+    DeploymentReverted {
+        /// The debug message recorded during the deployment.
+        debug_message: String,
+    },
+    #[error("Deployment failed: {0}")]
+    DeploymentFailed(DispatchFailure),
+    #[error("Contract call failed: {0}")]
+    CallFailed(DispatchFailure),
+    /// Returned by `StrictExecution` when a call reverted.
+    #[error(
+        "Contract call reverted{}{}",
+        error_suffix(error),
+        debug_suffix(debug_message)
+    )]
+    Reverted {
+        /// The raw data returned by the contract.
+        data: Vec<u8>,
+        /// The error returned by the message, for messages returning a `Result` (see `ExecCall::with_error_type`). It
+        /// has the message's error type, so it can be printed with `{:?}`.
+        error: Option<Box<dyn std::fmt::Debug + Send + Sync>>,
+        /// The debug message recorded during the call.
+        debug_message: String,
+    },
+}
+
+impl Error {
+    /// Returns the debug message recorded during a call that reverted.
+    pub fn debug_message(&self) -> Option<&str> {
+        match self {
+            Self::DeploymentReverted { debug_message } | Self::Reverted { debug_message, .. } => {
+                Some(debug_message)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn error_suffix(error: &Option<Box<dyn std::fmt::Debug + Send + Sync>>) -> String {
+    match error {
+        Some(error) => format!(" with {error:?}"),
+        None => String::new(),
+    }
+}
+
+fn debug_suffix(debug_message: &str) -> String {
+    if debug_message.is_empty() {
+        String::new()
+    } else {
+        format!(" (debug message: {})", debug_message.trim_end())
+    }
 }
 
 pub trait Connection<R: frame_system::Config> {
-    fn upload_code(&mut self, call: UploadCall) -> Result<HashFor<R>, Error>;
+    fn upload_code(&mut self, call: UploadCall) -> Result<UploadResult<HashFor<R>>, Error>;
 
     fn instantiate<T: Send>(
         &mut self,
@@ -42,19 +122,44 @@ pub trait Connection<R: frame_system::Config> {
     ) -> Result<ContractReadResult<T>, Error>;
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct ContractResult<R> {
-    pub gas_consumed: Weight,
-    pub gas_required: Weight,
-    pub result: R,
-    pub events: Vec<ContractEvent>,
-    pub reverted: bool,
-    pub debug_message: Vec<u8>,
-    pub storage_deposit: StorageDeposit<u128>,
+/// The data returned by a call together with the result of decoding it, so that the data is kept when it doesn't
+/// decode into the type of the message.
+#[derive(Debug)]
+struct WithData<T> {
+    data: Vec<u8>,
+    decoded: Result<T, scale::Error>,
 }
 
-pub type ContractInstantiateResult<AccountId> = ContractResult<AccountId>;
+impl<T: scale::Decode> scale::Decode for WithData<T> {
+    fn decode<I: scale::Input>(input: &mut I) -> Result<Self, scale::Error> {
+        let len = input
+            .remaining_len()?
+            .ok_or("The length of the returned data is unknown")?;
+        let mut data = vec![0; len];
+        input.read(&mut data)?;
+        let decoded = T::decode(&mut data.as_slice());
 
-pub type ContractExecResult<R> = ContractResult<R>;
+        Ok(Self { data, decoded })
+    }
+}
+
+/// Turns the result of a call decoded with `WithData` into the result of the call, failing the way backends do when the
+/// data doesn't decode.
+fn decode_result<T>(result: ContractResult<WithData<T>>) -> Result<ContractResult<T>, Error> {
+    let decoded = result.result.decoded.map_err(|err| {
+        Error::DecodingError(format!(
+            "Failed to decode the result of calling a contract: {err:?}",
+        ))
+    })?;
 
-pub type ContractReadResult<R> = ContractResult<R>;
+    Ok(ContractResult {
+        gas_consumed: result.gas_consumed,
+        gas_required: result.gas_required,
+        result: decoded,
+        events: result.events,
+        runtime_events: result.runtime_events,
+        reverted: result.reverted,
+        debug_message: result.debug_message,
+        storage_deposit: result.storage_deposit,
+    })
+}
@@ -0,0 +1,66 @@
+use super::*;
+
+/// Adds `with_strict_execution` to drink connections.
+pub trait StrictExecutionExt<R: frame_system::Config>: Connection<R> + Sized {
+    /// Makes `execute` fail on reverted calls, see `StrictExecution`.
+    fn with_strict_execution(self) -> StrictExecution<Self> {
+        StrictExecution::new(self)
+    }
+}
+
+impl<R: frame_system::Config, C: Connection<R>> StrictExecutionExt<R> for C {}
+
+/// A connection on which `execute` returns `Error::Reverted` for calls that reverted, instead of an `Ok` result with
+/// `reverted` set that is easy to miss. The changes of a reverted call are rolled back either way.
+///
+/// Queries are not affected, as they are rolled back anyway.
+pub struct StrictExecution<C> {
+    connection: C,
+}
+
+impl<C> StrictExecution<C> {
+    pub fn new(connection: C) -> Self {
+        Self { connection }
+    }
+}
+
+connection_wrapper!(StrictExecution);
+
+impl<C: Connection<R>, R: frame_system::Config> Connection<R> for StrictExecution<C> {
+    fn upload_code(&mut self, call: UploadCall) -> Result<UploadResult<HashFor<R>>, Error> {
+        self.connection.upload_code(call)
+    }
+
+    fn instantiate<T: Send>(
+        &mut self,
+        call: InstantiateCall<T>,
+    ) -> Result<ContractInstantiateResult<R::AccountId>, Error> {
+        self.connection.instantiate(call)
+    }
+
+    fn execute<T: scale::Decode + Send + std::fmt::Debug>(
+        &mut self,
+        call: ExecCall<T>,
+    ) -> Result<ContractExecResult<T>, Error> {
+        let error_decoder = call.error_decoder;
+        let result = self.connection.execute(call.decoded_as::<WithData<T>>())?;
+
+        if result.reverted {
+            let data = result.result.data;
+            return Err(Error::Reverted {
+                error: error_decoder.and_then(|decode| decode(&data)),
+                data,
+                debug_message: String::from_utf8_lossy(&result.debug_message).into_owned(),
+            });
+        }
+
+        decode_result(result)
+    }
+
+    fn query<T: scale::Decode + Send + std::fmt::Debug>(
+        &mut self,
+        call: impl Into<QueryArgs<T>>,
+    ) -> Result<ContractReadResult<T>, Error> {
+        self.connection.query(call)
+    }
+}
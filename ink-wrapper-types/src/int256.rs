@@ -0,0 +1,107 @@
+pub use primitive_types::U256;
+
+/// A signed 256-bit integer, used by generated wrappers for `i256` in the metadata.
+///
+/// The value is kept as its two's complement bits, which is also how it's SCALE-encoded. Convert it from and into
+/// `i128` or use the bits directly to work with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, scale::Encode, scale::Decode)]
+pub struct I256(pub U256);
+
+impl From<i128> for I256 {
+    fn from(value: i128) -> Self {
+        if value < 0 {
+            // `!value` is `-value - 1`, which is non-negative, so the cast is lossless.
+            Self(!U256::from(!value as u128))
+        } else {
+            Self(U256::from(value as u128))
+        }
+    }
+}
+
+impl TryFrom<I256> for i128 {
+    type Error = &'static str;
+
+    fn try_from(value: I256) -> Result<Self, Self::Error> {
+        const OVERFLOW: &str = "integer overflow when casting to i128";
+        let max = U256::from(i128::MAX as u128);
+
+        if value.0.bit(255) {
+            let inverted = !value.0;
+            if inverted > max {
+                return Err(OVERFLOW);
+            }
+            Ok(!(inverted.as_u128() as i128))
+        } else {
+            if value.0 > max {
+                return Err(OVERFLOW);
+            }
+            Ok(value.0.as_u128() as i128)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use scale::{Decode, Encode};
+
+    use super::*;
+
+    /// The smallest and the largest value an `I256` can hold.
+    fn min() -> I256 {
+        I256(U256::one() << 255)
+    }
+
+    fn max() -> I256 {
+        I256(U256::MAX >> 1)
+    }
+
+    #[test]
+    fn test_i128_round_trip() {
+        for value in [0, 1, -1, 42, -42, i128::MIN, i128::MAX] {
+            assert_eq!(i128::try_from(I256::from(value)), Ok(value));
+        }
+    }
+
+    #[test]
+    fn test_negative_values_are_twos_complement() {
+        assert_eq!(I256::from(-1), I256(U256::MAX));
+        assert_eq!(I256::from(-2), I256(U256::MAX - 1));
+        assert_eq!(I256::from(i128::MIN), I256(!U256::from(i128::MAX as u128)));
+    }
+
+    #[test]
+    fn test_values_out_of_i128_range() {
+        let too_large = I256(U256::from(i128::MAX as u128) + 1);
+        let too_small = I256(!U256::from(i128::MAX as u128) - 1);
+
+        for value in [too_large, too_small, min(), max()] {
+            assert_eq!(
+                i128::try_from(value),
+                Err("integer overflow when casting to i128")
+            );
+        }
+    }
+
+    #[test]
+    fn test_i256_encoding() {
+        assert_eq!(I256::from(1).encode(), [&[1][..], &[0; 31]].concat());
+        assert_eq!(I256::from(-1).encode(), [0xff; 32]);
+        assert_eq!(min().encode(), [&[0; 31][..], &[0x80]].concat());
+        assert_eq!(max().encode(), [&[0xff; 31][..], &[0x7f]].concat());
+
+        for value in [I256::from(-1), I256::from(i128::MIN), min(), max()] {
+            assert_eq!(I256::decode(&mut &value.encode()[..]), Ok(value));
+        }
+    }
+
+    #[test]
+    fn test_u256_encoding() {
+        assert_eq!(U256::from(1).encode(), [&[1][..], &[0; 31]].concat());
+        assert_eq!(U256::MAX.encode(), [0xff; 32]);
+
+        for value in [U256::zero(), U256::from(u128::MAX) + 1, U256::MAX] {
+            assert_eq!(U256::decode(&mut &value.encode()[..]), Ok(value));
+        }
+        assert!(U256::decode(&mut &[0xff; 31][..]).is_err());
+    }
+}
@@ -1,14 +1,43 @@
+#[cfg(any(feature = "drink", feature = "subxt"))]
+mod async_connection;
+#[cfg(any(feature = "drink", feature = "subxt"))]
+pub use async_connection::*;
+#[cfg(any(feature = "drink", feature = "subxt"))]
+mod chain_event;
+#[cfg(any(feature = "drink", feature = "subxt"))]
+pub use chain_event::*;
+#[cfg(any(feature = "drink", feature = "subxt"))]
+mod contract_result;
+#[cfg(any(feature = "drink", feature = "subxt"))]
+pub use contract_result::*;
+#[cfg(any(feature = "drink", feature = "subxt"))]
+mod dispatch_error;
+#[cfg(any(feature = "drink", feature = "subxt"))]
+pub use dispatch_error::*;
+
 #[cfg(feature = "drink")]
 pub mod drink_types;
 #[cfg(feature = "drink")]
 pub use drink_types::*;
 
+#[cfg(feature = "subxt")]
+pub mod subxt_types;
+
+mod call_outcome;
+pub use call_outcome::*;
+
 mod calls;
 pub use calls::*;
 
 pub mod utils;
 pub use utils::*;
 
+mod int256;
+pub use int256::*;
+
+/// Re-exported for generated wrappers, which use `bitvec::vec::BitVec` for bit sequences.
+pub use bitvec;
+
 use ink_primitives::AccountId;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -18,6 +47,8 @@ pub struct ContractEvent {
     pub account_id: AccountId,
     /// The unparsed data of the event.
     pub data: Vec<u8>,
+    /// The topics of the event. Unless the event is anonymous, the first one is the signature topic identifying it.
+    pub topics: Vec<[u8; 32]>,
 }
 
 /// Represents a collection of events emitted by contracts in a single transaction.
@@ -28,7 +59,29 @@ pub struct ContractEvents {
 /// A trait that allows to decode events emitted by a specific contract.
 pub trait EventSource: Copy + Into<AccountId> {
     /// The type to decode the emitted events into.
-    type Event: scale::Decode;
+    type Event: EventDecode;
+}
+
+/// A type that events of a contract can be decoded into, implemented by the `event::Event` enum of generated wrappers.
+pub trait EventDecode: Sized {
+    /// The labels of the events along with their signature topics.
+    ///
+    /// Anonymous events are left out when the metadata marks them as such (ink! 5). Wrappers generated from ink! 4
+    /// metadata list them as well, but they never match, as they are emitted without a signature topic.
+    const SIGNATURE_TOPICS: &'static [(&'static str, [u8; 32])];
+
+    /// Decodes an event emitted by a contract of this type.
+    fn decode_event(topics: &[[u8; 32]], data: &[u8]) -> Result<Self, scale::Error>;
+
+    /// Returns the label of the event with the given topics, if its signature topic is one of `SIGNATURE_TOPICS`.
+    fn match_signature(topics: &[[u8; 32]]) -> Option<&'static str> {
+        let signature_topic = topics.first()?;
+
+        Self::SIGNATURE_TOPICS
+            .iter()
+            .find(|(_, topic)| topic == signature_topic)
+            .map(|(label, _)| *label)
+    }
 }
 
 impl ContractEvents {
@@ -45,12 +98,23 @@ impl ContractEvents {
         events: I,
         contract: C,
     ) -> Vec<Result<C::Event, scale::Error>> {
-        use scale::Decode as _;
-
         events
             .into_iter()
             .filter(|e| e.account_id == contract.into())
-            .map(|e| C::Event::decode(&mut e.data.as_slice()))
+            .map(|e| C::Event::decode_event(&e.topics, &e.data))
+            .collect()
+    }
+
+    /// Returns the events whose signature topic matches one of the events of `E`, no matter which contract emitted
+    /// them, together with the address of the emitter.
+    ///
+    /// Useful for events of contracts that were not called directly, e.g. a token transferred by the called contract.
+    /// Anonymous events are skipped, as they can't be recognized.
+    pub fn by_signature<E: EventDecode>(&self) -> Vec<(AccountId, Result<E, scale::Error>)> {
+        self.events
+            .iter()
+            .filter(|e| E::match_signature(&e.topics).is_some())
+            .map(|e| (e.account_id, E::decode_event(&e.topics, &e.data)))
             .collect()
     }
 }
@@ -0,0 +1,486 @@
+use pallet_contracts_primitives::{
+    Code, CodeUploadReturnValue, ExecReturnValue, InstantiateReturnValue, StorageDeposit,
+};
+use scale::{Decode, Encode};
+use sp_weights::Weight;
+use subxt::{
+    blocks::ExtrinsicEvents,
+    config::ExtrinsicParams,
+    dynamic::Value,
+    events::EventDetails,
+    ext::scale_value::Composite,
+    tx::{Signer, TxPayload},
+    utils::AccountId32,
+    Config, OnlineClient,
+};
+
+use super::*;
+use crate::{
+    AsyncConnection, CallOverrides, ChainEvent, ContractEvent, ContractExecResult,
+    ContractInstantiateResult, ContractReadResult, ContractResult, ContractsError, DispatchFailure,
+    ExecCall, InstantiateCall, QueryArgs, TxStatus, UploadCall, UploadResult,
+};
+
+/// A connection to a live chain, signing the submitted transactions with `signer`.
+///
+/// Every call is dry-run first through the `ContractsApi` runtime API. The dry run provides the gas limit for the
+/// transaction and the value returned by the contract, while the events are taken from the submitted extrinsic. How
+/// long to wait for the extrinsic is controlled by the `tx_status` of the call (`Finalized` by default) - if it's
+/// `Submitted`, the returned result has no events.
+pub struct SignedConnection<C: Config, S: Signer<C>> {
+    client: OnlineClient<C>,
+    signer: S,
+}
+
+impl<C, S> SignedConnection<C, S>
+where
+    C: Config<AccountId = AccountId32>,
+    <C::ExtrinsicParams as ExtrinsicParams<C>>::OtherParams: Default,
+    S: Signer<C>,
+{
+    /// Create a connection using an existing client.
+    pub fn new(client: OnlineClient<C>, signer: S) -> Self {
+        Self { client, signer }
+    }
+
+    /// Connect to the node at the given url.
+    pub async fn from_url(url: impl AsRef<str>, signer: S) -> Result<Self, Error> {
+        let client = OnlineClient::from_url(url).await?;
+        Ok(Self::new(client, signer))
+    }
+
+    /// The underlying subxt client.
+    pub fn client(&self) -> &OnlineClient<C> {
+        &self.client
+    }
+
+    pub async fn upload_code(&self, call: UploadCall) -> Result<UploadResult<C::Hash>, Error> {
+        let origin = self.signer.account_id();
+        // `Determinism::Enforced` is encoded as the first variant of the enum.
+        let determinism = 0u8;
+
+        let dry_run: Result<CodeUploadReturnValue<C::Hash, u128>, DispatchError> = self
+            .dry_run(
+                "ContractsApi_upload_code",
+                (origin, &call.wasm, None::<u128>, determinism),
+            )
+            .await?;
+        let code_hash = dry_run
+            .map_err(|err| Error::UploadFailed(self.dispatch_failure(err)))?
+            .code_hash;
+        if code_hash.as_ref() != call.expected_code_hash {
+            return Err(Error::CodeHashMismatch);
+        }
+
+        let payload = subxt::dynamic::tx(
+            "Contracts",
+            "upload_code",
+            Composite::unnamed([
+                Value::from_bytes(&call.wasm),
+                Value::unnamed_variant("None", []),
+                Value::unnamed_variant("Enforced", []),
+            ]),
+        );
+        let tx_events = self.submit(&payload, call.tx_status).await?;
+        let runtime_events = tx_events.as_ref().map(extract_runtime_events).transpose()?;
+
+        Ok(UploadResult {
+            code_hash,
+            runtime_events: runtime_events.unwrap_or_default(),
+        })
+    }
+
+    pub async fn instantiate<T: Send>(
+        &self,
+        call: InstantiateCall<T>,
+    ) -> Result<ContractInstantiateResult<AccountId32>, Error> {
+        let origin = self.signer_as_caller(&call.overrides)?;
+
+        let dry_run: DryRunResult<Result<InstantiateReturnValue<AccountId32>, DispatchError>> =
+            self.dry_run(
+                "ContractsApi_instantiate",
+                (
+                    origin,
+                    call.value,
+                    call.overrides.gas_limit,
+                    call.overrides.storage_deposit_limit,
+                    Code::Existing(call.code_hash),
+                    &call.data,
+                    &call.salt,
+                ),
+            )
+            .await?;
+
+        let dry_run_address = match &dry_run.result {
+            Ok(exec_result) if exec_result.result.did_revert() => Err(Error::DeploymentReverted),
+            Err(err) => Err(Error::DeploymentFailed(self.dispatch_failure(*err))),
+            Ok(exec_result) => Ok(exec_result.account_id.clone()),
+        }?;
+
+        let payload = subxt::dynamic::tx(
+            "Contracts",
+            "instantiate",
+            Composite::unnamed([
+                Value::u128(call.value),
+                weight_value(call.overrides.gas_limit.unwrap_or(dry_run.gas_required)),
+                storage_deposit_limit_value(call.overrides.storage_deposit_limit),
+                Value::from_bytes(call.code_hash),
+                Value::from_bytes(&call.data),
+                Value::from_bytes(&call.salt),
+            ]),
+        );
+        let tx_events = self.submit(&payload, call.tx_status).await?;
+
+        let contract_address = match &tx_events {
+            Some(tx_events) => instantiated_address(tx_events)?.unwrap_or(dry_run_address),
+            None => dry_run_address,
+        };
+        let events = tx_events.as_ref().map(extract_events).transpose()?;
+        let runtime_events = tx_events.as_ref().map(extract_runtime_events).transpose()?;
+
+        Ok(ContractResult {
+            gas_consumed: dry_run.gas_consumed,
+            gas_required: dry_run.gas_required,
+            result: contract_address,
+            events: events.unwrap_or_default(),
+            runtime_events: runtime_events.unwrap_or_default(),
+            reverted: false,
+            debug_message: dry_run.debug_message,
+            storage_deposit: dry_run.storage_deposit,
+        })
+    }
+
+    /// Executes the call on chain.
+    ///
+    /// If the dry run reverts, the call is not submitted (it would fail anyway) and the dry run result is returned with
+    /// `reverted` set.
+    pub async fn execute<T: scale::Decode + Send + std::fmt::Debug>(
+        &self,
+        call: ExecCall<T>,
+    ) -> Result<ContractExecResult<T>, Error> {
+        let origin = self.signer_as_caller(&call.overrides)?;
+        let mut result = self
+            .call_contract(
+                origin,
+                call.account_id,
+                call.value,
+                call.data.clone(),
+                &call.overrides,
+            )
+            .await?;
+        if result.reverted {
+            return Ok(result);
+        }
+
+        let payload = subxt::dynamic::tx(
+            "Contracts",
+            "call",
+            Composite::unnamed([
+                Value::unnamed_variant("Id", [Value::from_bytes(call.account_id)]),
+                Value::u128(call.value),
+                weight_value(call.overrides.gas_limit.unwrap_or(result.gas_required)),
+                storage_deposit_limit_value(call.overrides.storage_deposit_limit),
+                Value::from_bytes(&call.data),
+            ]),
+        );
+        if let Some(tx_events) = self.submit(&payload, call.tx_status).await? {
+            result.events = extract_events(&tx_events)?;
+            result.runtime_events = extract_runtime_events(&tx_events)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Like `execute`, but only dry-runs the call. The result contains no events.
+    ///
+    /// Unlike transactions, queries can be made on behalf of any caller.
+    pub async fn query<T: scale::Decode + Send + std::fmt::Debug>(
+        &self,
+        call: impl Into<QueryArgs<T>>,
+    ) -> Result<ContractReadResult<T>, Error> {
+        let args = call.into();
+        let origin = match args.overrides.caller {
+            Some(caller) => to_account_id32(&caller),
+            None => self.signer.account_id(),
+        };
+
+        self.call_contract(
+            origin,
+            args.account_id,
+            args.value,
+            args.data,
+            &args.overrides,
+        )
+        .await
+    }
+
+    /// Returns the signer's account, checking that it's the caller requested for the transaction (if any).
+    fn signer_as_caller(&self, overrides: &CallOverrides) -> Result<AccountId32, Error> {
+        let signer = self.signer.account_id();
+        match overrides.caller {
+            Some(caller) if to_account_id32(&caller) != signer => Err(Error::CallerNotSigner),
+            _ => Ok(signer),
+        }
+    }
+
+    async fn call_contract<T: scale::Decode + Send + std::fmt::Debug>(
+        &self,
+        origin: AccountId32,
+        address: ink_primitives::AccountId,
+        value: u128,
+        data: Vec<u8>,
+        overrides: &CallOverrides,
+    ) -> Result<ContractResult<T>, Error> {
+        let address = to_account_id32(&address);
+
+        let dry_run: DryRunResult<Result<ExecReturnValue, DispatchError>> = self
+            .dry_run(
+                "ContractsApi_call",
+                (
+                    origin,
+                    address,
+                    value,
+                    overrides.gas_limit,
+                    overrides.storage_deposit_limit,
+                    data,
+                ),
+            )
+            .await?;
+
+        let exec_result = dry_run
+            .result
+            .map_err(|err| Error::CallFailed(self.dispatch_failure(err)))?;
+        let message_result = T::decode(&mut exec_result.data.as_slice()).map_err(|err| {
+            Error::DecodingError(format!(
+                "Failed to decode the result of calling a contract: {err:?}",
+            ))
+        })?;
+
+        Ok(ContractResult {
+            gas_consumed: dry_run.gas_consumed,
+            gas_required: dry_run.gas_required,
+            result: message_result,
+            events: vec![],
+            runtime_events: vec![],
+            reverted: exec_result.did_revert(),
+            debug_message: dry_run.debug_message,
+            storage_deposit: dry_run.storage_deposit,
+        })
+    }
+
+    async fn dry_run<R: Decode>(&self, method: &str, args: impl Encode) -> Result<R, Error> {
+        let args = args.encode();
+        let result = self
+            .client
+            .runtime_api()
+            .at_latest()
+            .await?
+            .call_raw(method, Some(&args))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Names the module errors using the metadata of the chain, decoding the ones of the contracts pallet.
+    fn dispatch_failure(&self, error: DispatchError) -> DispatchFailure {
+        let DispatchError::Module(module) = error else {
+            return DispatchFailure::Other(format!("{error:?}"));
+        };
+
+        let metadata = self.client.metadata();
+        let named = metadata.pallet_by_index(module.index).and_then(|pallet| {
+            let variant = pallet.error_variant_by_index(module.error[0])?;
+            Some((pallet.name(), variant.name.as_str()))
+        });
+
+        match named {
+            Some(("Contracts", name)) => {
+                DispatchFailure::Contracts(ContractsError::from_name(name))
+            }
+            Some((pallet, name)) => DispatchFailure::Other(format!("{pallet}::{name}")),
+            None => DispatchFailure::Other(format!("{module:?}")),
+        }
+    }
+
+    /// Signs and submits the transaction, waiting for the given status. Returns `None` if the status is `Submitted`,
+    /// since there are no events to return yet.
+    async fn submit<Call: TxPayload>(
+        &self,
+        payload: &Call,
+        tx_status: TxStatus,
+    ) -> Result<Option<ExtrinsicEvents<C>>, Error> {
+        let tx = self.client.tx();
+
+        let events = match tx_status {
+            TxStatus::Submitted => {
+                tx.sign_and_submit_default(payload, &self.signer).await?;
+                return Ok(None);
+            }
+            TxStatus::InBlock => {
+                tx.sign_and_submit_then_watch_default(payload, &self.signer)
+                    .await?
+                    .wait_for_in_block()
+                    .await?
+                    .wait_for_success()
+                    .await?
+            }
+            TxStatus::Finalized => {
+                tx.sign_and_submit_then_watch_default(payload, &self.signer)
+                    .await?
+                    .wait_for_finalized_success()
+                    .await?
+            }
+        };
+
+        Ok(Some(events))
+    }
+}
+
+#[async_trait::async_trait]
+impl<C, S> AsyncConnection for SignedConnection<C, S>
+where
+    C: Config<AccountId = AccountId32>,
+    <C::ExtrinsicParams as ExtrinsicParams<C>>::OtherParams: Default + Send,
+    S: Signer<C> + Send + Sync,
+{
+    type AccountId = AccountId32;
+    type Hash = C::Hash;
+    type Error = Error;
+
+    async fn upload_code(&self, call: UploadCall) -> Result<UploadResult<C::Hash>, Error> {
+        SignedConnection::upload_code(self, call).await
+    }
+
+    async fn instantiate<T: Send>(
+        &self,
+        call: InstantiateCall<T>,
+    ) -> Result<ContractInstantiateResult<AccountId32>, Error> {
+        SignedConnection::instantiate(self, call).await
+    }
+
+    async fn execute<T: scale::Decode + Send + std::fmt::Debug>(
+        &self,
+        call: ExecCall<T>,
+    ) -> Result<ContractExecResult<T>, Error> {
+        SignedConnection::execute(self, call).await
+    }
+
+    async fn query<T: scale::Decode + Send + std::fmt::Debug>(
+        &self,
+        call: impl Into<QueryArgs<T>> + Send,
+    ) -> Result<ContractReadResult<T>, Error> {
+        SignedConnection::query(self, call).await
+    }
+}
+
+/// The result of a `ContractsApi` dry run.
+///
+/// Mirrors `pallet_contracts_primitives::ContractResult`, except that the events collected during the dry run (if the
+/// node collects them at all) are not decoded - the events of the submitted extrinsic are used instead.
+#[derive(Decode)]
+struct DryRunResult<R> {
+    gas_consumed: Weight,
+    gas_required: Weight,
+    storage_deposit: StorageDeposit<u128>,
+    debug_message: Vec<u8>,
+    result: R,
+}
+
+fn weight_value(weight: Weight) -> Value {
+    Value::named_composite([
+        ("ref_time", Value::u128(weight.ref_time() as u128)),
+        ("proof_size", Value::u128(weight.proof_size() as u128)),
+    ])
+}
+
+fn storage_deposit_limit_value(limit: Option<u128>) -> Value {
+    match limit {
+        Some(limit) => Value::unnamed_variant("Some", [Value::u128(limit)]),
+        None => Value::unnamed_variant("None", []),
+    }
+}
+
+fn to_account_id32(account_id: &ink_primitives::AccountId) -> AccountId32 {
+    AccountId32(*AsRef::<[u8; 32]>::as_ref(account_id))
+}
+
+/// Returns all `Contracts` pallet events of the given variant.
+fn contracts_events<'a, C: Config>(
+    events: &'a ExtrinsicEvents<C>,
+    variant: &'a str,
+) -> impl Iterator<Item = Result<EventDetails<C>, Error>> + 'a {
+    events.iter().filter_map(move |event| match event {
+        Ok(event) if event.pallet_name() == "Contracts" && event.variant_name() == variant => {
+            Some(Ok(event))
+        }
+        Ok(_) => None,
+        Err(err) => Some(Err(err.into())),
+    })
+}
+
+fn extract_events<C: Config>(events: &ExtrinsicEvents<C>) -> Result<Vec<ContractEvent>, Error> {
+    contracts_events(events, "ContractEmitted")
+        .map(|event| {
+            let event = event?;
+            let (contract, data) = <([u8; 32], Vec<u8>)>::decode(&mut event.field_bytes())
+                .map_err(|err| {
+                    Error::DecodingError(format!("Failed to decode ContractEmitted: {err:?}"))
+                })?;
+            let topics = event_topics(&event)?;
+
+            Ok(ContractEvent {
+                account_id: contract.into(),
+                data,
+                topics,
+            })
+        })
+        .collect()
+}
+
+/// Decodes all the events of the extrinsic with `ChainEvent::decode`.
+fn extract_runtime_events<C: Config>(
+    events: &ExtrinsicEvents<C>,
+) -> Result<Vec<ChainEvent>, Error> {
+    events
+        .iter()
+        .map(|event| {
+            let event = event?;
+            Ok(ChainEvent::decode(
+                event.pallet_name(),
+                event.variant_name(),
+                event_topics(&event)?,
+                event.field_bytes(),
+            ))
+        })
+        .collect()
+}
+
+fn event_topics<C: Config>(event: &EventDetails<C>) -> Result<Vec<[u8; 32]>, Error> {
+    event
+        .topics()
+        .iter()
+        .map(|topic| {
+            topic.as_ref().try_into().map_err(|_| {
+                Error::DecodingError("Event topics are expected to be 32 bytes".to_string())
+            })
+        })
+        .collect()
+}
+
+/// Finds the address of the instantiated contract. If the constructor instantiated other contracts, their events come
+/// first, so the last `Instantiated` event is the one we're looking for.
+fn instantiated_address<C: Config>(
+    events: &ExtrinsicEvents<C>,
+) -> Result<Option<AccountId32>, Error> {
+    contracts_events(events, "Instantiated")
+        .map(|event| {
+            let (_deployer, contract) =
+                <(AccountId32, AccountId32)>::decode(&mut event?.field_bytes()).map_err(|err| {
+                    Error::DecodingError(format!("Failed to decode Instantiated: {err:?}"))
+                })?;
+
+            Ok(contract)
+        })
+        .last()
+        .transpose()
+}
@@ -0,0 +1,28 @@
+mod client;
+
+pub use client::*;
+use sp_runtime::DispatchError;
+
+use crate::DispatchFailure;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Decoding error: {0}")]
+    DecodingError(String),
+    #[error("Upload failed: {0}")]
+    UploadFailed(DispatchFailure),
+    #[error("Code hash mismatch")]
+    CodeHashMismatch,
+    #[error("Deployment reverted")]
+    DeploymentReverted,
+    #[error("Deployment failed: {0}")]
+    DeploymentFailed(DispatchFailure),
+    #[error("Contract call failed: {0}")]
+    CallFailed(DispatchFailure),
+    #[error(
+        "Transactions can only be made by the signer of the connection, not by a different caller"
+    )]
+    CallerNotSigner,
+    #[error("Subxt error: {0}")]
+    Subxt(#[from] subxt::Error),
+}
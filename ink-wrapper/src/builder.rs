@@ -0,0 +1,154 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::{codegen::generate_with_options, format_wrapper, metadata::ContractMetadata, Options};
+
+/// Generates a contract wrapper from a `build.rs` script.
+///
+/// The wrapper is formatted and written to `OUT_DIR`, and cargo is told to rerun the build script whenever the metadata
+/// or the WASM changes, so the wrapper never goes out of date. In `main` of `build.rs`:
+///
+/// ```no_run
+/// ink_wrapper::Builder::new("../my_contract/target/ink/my_contract.json")
+///     .wasm_path("../my_contract/target/ink/my_contract.wasm")
+///     .generate()
+///     .expect("Failed to generate the wrapper for my_contract");
+/// ```
+///
+/// The generated module can then be included in the crate:
+///
+/// ```ignore
+/// mod my_contract {
+///     include!(concat!(env!("OUT_DIR"), "/my_contract.rs"));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Builder {
+    metadata_path: PathBuf,
+    wasm_path: Option<PathBuf>,
+    embed_wasm: bool,
+    output_file: Option<PathBuf>,
+    options: Options,
+}
+
+impl Builder {
+    /// Create a builder for the contract described by the metadata file at `metadata_path`.
+    pub fn new(metadata_path: impl Into<PathBuf>) -> Self {
+        Self {
+            metadata_path: metadata_path.into(),
+            wasm_path: None,
+            embed_wasm: false,
+            output_file: None,
+            options: Options::default(),
+        }
+    }
+
+    /// Set the path to the contract's WASM. If provided, the WASM will be embedded in the wrapper, making it possible
+    /// to upload the contract.
+    pub fn wasm_path(mut self, wasm_path: impl Into<PathBuf>) -> Self {
+        self.wasm_path = Some(wasm_path.into());
+        self
+    }
+
+    /// Embed the WASM from the `.contract` bundle given as the metadata file in the wrapper, making it possible to
+    /// upload the contract without a separate `wasm_path`. Can't be combined with `wasm_path`.
+    pub fn embed_wasm(mut self, embed_wasm: bool) -> Self {
+        self.embed_wasm = embed_wasm;
+        self
+    }
+
+    /// Set the file to write the wrapper to. Relative paths are resolved against `OUT_DIR`.
+    ///
+    /// Defaults to the name of the metadata file with an `.rs` extension, for example `my_contract.rs`.
+    pub fn output_file(mut self, output_file: impl Into<PathBuf>) -> Self {
+        self.output_file = Some(output_file.into());
+        self
+    }
+
+    /// Skip the items of the contract that can't be generated instead of failing, reporting them as cargo warnings.
+    ///
+    /// Types are replaced with stubs that fail to decode, while messages, constructors and events are left out.
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.options.lenient = lenient;
+        self
+    }
+
+    /// Make the messages return flattened results, see [Options::flatten_results].
+    pub fn flatten_results(mut self, flatten_results: bool) -> Self {
+        self.options.flatten_results = flatten_results;
+        self
+    }
+
+    /// Generate the wrapper and write it to the output file. Returns the path of the written file.
+    pub fn generate(self) -> Result<PathBuf> {
+        if self.embed_wasm && self.wasm_path.is_some() {
+            bail!("`embed_wasm` can't be used together with `wasm_path`");
+        }
+
+        println!("cargo:rerun-if-changed={}", self.metadata_path.display());
+        let mut metadata = ContractMetadata::from_file(&self.metadata_path)?;
+        if !self.embed_wasm {
+            metadata.wasm = None;
+        } else if metadata.wasm.is_none() {
+            bail!(
+                "{} is not a .contract bundle with the WASM",
+                self.metadata_path.display()
+            );
+        }
+
+        // The wrapper ends up in `OUT_DIR`, so `include_bytes!` needs an absolute path to find the WASM.
+        let wasm_path = match &self.wasm_path {
+            Some(wasm_path) => {
+                println!("cargo:rerun-if-changed={}", wasm_path.display());
+                let wasm_path = wasm_path.canonicalize().with_context(|| {
+                    format!("Failed to resolve WASM path {}", wasm_path.display())
+                })?;
+                metadata.verify_wasm(&wasm_path)?;
+                Some(wasm_path.to_string_lossy().into_owned())
+            }
+            None => None,
+        };
+
+        let (tokens, skipped) = generate_with_options(&metadata, wasm_path, &self.options)?;
+        for error in skipped {
+            println!("cargo:warning=ink-wrapper skipped {}", error);
+        }
+        let output_file = self.output_path()?;
+        fs::write(&output_file, format_wrapper(&metadata, tokens)?)
+            .with_context(|| format!("Failed to write {}", output_file.display()))?;
+
+        Ok(output_file)
+    }
+
+    fn output_path(&self) -> Result<PathBuf> {
+        let file_name = match &self.output_file {
+            Some(output_file) => output_file.clone(),
+            None => default_file_name(&self.metadata_path)?,
+        };
+
+        if file_name.is_absolute() {
+            return Ok(file_name);
+        }
+
+        let out_dir = env::var_os("OUT_DIR").ok_or_else(|| {
+            anyhow!("OUT_DIR is not set, the builder is meant to be used from a build script")
+        })?;
+
+        Ok(PathBuf::from(out_dir).join(file_name))
+    }
+}
+
+fn default_file_name(metadata_path: &Path) -> Result<PathBuf> {
+    let stem = metadata_path.file_stem().ok_or_else(|| {
+        anyhow!(
+            "Cannot derive the output file name from {}",
+            metadata_path.display()
+        )
+    })?;
+
+    Ok(PathBuf::from(stem).with_extension("rs"))
+}
@@ -0,0 +1,52 @@
+/// The number of unchanged lines shown around each change in a diff.
+const CONTEXT_LINES: usize = 3;
+
+/// Compares an existing wrapper with a freshly generated one, ignoring formatting and comments (like the header).
+///
+/// Returns `None` if the wrappers are the same, or a line diff of the formatted wrappers otherwise. Lines starting with
+/// `-` are only in the existing wrapper, lines starting with `+` only in the generated one.
+pub fn diff_wrappers(existing: &str, generated: &str) -> Option<String> {
+    let existing = normalize(existing);
+    let generated = normalize(generated);
+    if existing == generated {
+        return None;
+    }
+
+    let lines = diff::lines(&existing, &generated);
+    let changed = |i: usize| !matches!(lines[i], diff::Result::Both(..));
+    let mut output = String::new();
+    let mut last_shown = None;
+
+    for (i, line) in lines.iter().enumerate() {
+        let near_change = (i.saturating_sub(CONTEXT_LINES)
+            ..(i + CONTEXT_LINES + 1).min(lines.len()))
+            .any(changed);
+        if !near_change {
+            continue;
+        }
+
+        if last_shown.map_or(i > 0, |last| last + 1 < i) {
+            output.push_str("...\n");
+        }
+        last_shown = Some(i);
+
+        let (sign, text) = match line {
+            diff::Result::Left(text) => ('-', text),
+            diff::Result::Right(text) => ('+', text),
+            diff::Result::Both(text, _) => (' ', text),
+        };
+        output.push(sign);
+        output.push_str(text);
+        output.push('\n');
+    }
+
+    Some(output)
+}
+
+/// Formats the source the same way generated wrappers are formatted. Sources that don't parse are left as they are.
+fn normalize(source: &str) -> String {
+    match syn::parse_file(source) {
+        Ok(file) => prettyplease::unparse(&file),
+        Err(_) => source.to_string(),
+    }
+}
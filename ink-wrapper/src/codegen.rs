@@ -1,53 +1,159 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashSet};
 
 use ink_metadata::{ConstructorSpec, EventSpec, InkProject, MessageParamSpec, MessageSpec};
 use proc_macro2::Ident;
 use quote::*;
 use scale_info::{
-    form::PortableForm, Type, TypeDef, TypeDefArray, TypeDefCompact, TypeDefComposite,
-    TypeDefPrimitive, TypeDefSequence, TypeDefTuple, TypeDefVariant,
+    form::PortableForm, Type, TypeDef, TypeDefArray, TypeDefBitSequence, TypeDefCompact,
+    TypeDefComposite, TypeDefPrimitive, TypeDefSequence, TypeDefTuple, TypeDefVariant,
 };
 
-use crate::extensions::*;
+use crate::{
+    extensions::*,
+    metadata::{ContractMetadata, MetadataVersion},
+    GenerateError, Item,
+};
 
 type MessageList<'a> = Vec<&'a MessageSpec<PortableForm>>;
 
 /// Generates the full wrapper for the contract.
 pub fn generate(
-    metadata: &InkProject,
-    code_hash: String,
+    contract: &ContractMetadata,
     wasm_path: Option<String>,
-) -> proc_macro2::TokenStream {
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let (tokens, _) = generate_with_options(contract, wasm_path, &Options::default())?;
+    Ok(tokens)
+}
+
+/// Generates the wrapper for the contract, leaving out the items that can't be generated instead of failing.
+///
+/// Messages, constructors and events that can't be generated are left out of the wrapper, and custom types are replaced
+/// with stubs that fail to decode. The errors for these items are returned along with the wrapper.
+pub fn generate_lenient(
+    contract: &ContractMetadata,
+    wasm_path: Option<String>,
+) -> Result<(proc_macro2::TokenStream, Vec<GenerateError>), GenerateError> {
+    let options = Options {
+        lenient: true,
+        ..Options::default()
+    };
+    generate_with_options(contract, wasm_path, &options)
+}
+
+/// Generates the wrapper for the contract with the given options. In lenient mode, the errors for the skipped items
+/// are returned along with the wrapper.
+pub fn generate_with_options(
+    contract: &ContractMetadata,
+    wasm_path: Option<String>,
+    options: &Options,
+) -> Result<(proc_macro2::TokenStream, Vec<GenerateError>), GenerateError> {
+    let mut skipped = Skipped::new(options.lenient);
+    let tokens = generate_wrapper(contract, wasm_path, None, options, &mut skipped)?;
+    Ok((tokens, skipped.errors))
+}
+
+/// Options for generating a wrapper.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Options {
+    /// Skip the items that can't be generated instead of failing, see [generate_lenient].
+    pub lenient: bool,
+    /// Decode the results of messages into `ink_wrapper_types::Flat<T>` (for messages returning `T`) and
+    /// `ink_wrapper_types::FlatResult<T, E>` (for messages returning `Result<T, E>`) instead of nested `Result`s with
+    /// `InkLangError`. Both can be turned into a `Result<T, CallError<E>>`, e.g. with `ContractResult::ok`.
+    pub flatten_results: bool,
+}
+
+/// Generates the wrapper for the contract with the custom types in `shared` imported from the shared module instead of
+/// being defined in the wrapper. In lenient mode, the errors for the skipped items are returned along with the wrapper.
+pub(crate) fn generate_with_shared_types(
+    contract: &ContractMetadata,
+    wasm_path: Option<String>,
+    shared: &SharedTypes,
+    options: &Options,
+) -> Result<(proc_macro2::TokenStream, Vec<GenerateError>), GenerateError> {
+    let mut skipped = Skipped::new(options.lenient);
+    let tokens = generate_wrapper(contract, wasm_path, Some(shared), options, &mut skipped)?;
+    Ok((tokens, skipped.errors))
+}
+
+/// Generates the definitions of the custom types of the contract, keyed by the names they are defined with.
+pub(crate) fn custom_types(
+    contract: &ContractMetadata,
+    lenient: bool,
+) -> Result<Vec<(String, proc_macro2::TokenStream)>, GenerateError> {
+    define_custom_types(&contract.project, &mut Skipped::new(lenient))
+}
+
+/// Custom types that are defined once in a module shared by several wrappers.
+pub(crate) struct SharedTypes {
+    /// The path of the shared module, e.g. `crate::shared_types`.
+    pub module: syn::Path,
+    /// The definitions of the shared types, keyed by their names.
+    pub definitions: BTreeMap<String, proc_macro2::TokenStream>,
+}
+
+impl SharedTypes {
+    /// Returns true if the type defined as `definition` under `name` is defined in the shared module.
+    fn contains(&self, name: &str, definition: &proc_macro2::TokenStream) -> bool {
+        self.definitions
+            .get(name)
+            .map_or(false, |shared| shared.to_string() == definition.to_string())
+    }
+}
+
+fn generate_wrapper(
+    contract: &ContractMetadata,
+    wasm_path: Option<String>,
+    shared: Option<&SharedTypes>,
+    options: &Options,
+    skipped: &mut Skipped,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let metadata = &contract.project;
+
     let (top_level_messages, trait_messages) = group_messages(metadata);
 
-    let code_hash = hex_to_bytes(&code_hash);
+    let code_hash = hex_to_bytes(&contract.code_hash)?;
 
-    let upload = define_upload(wasm_path);
+    let upload = define_upload(wasm_path, contract.wasm.as_deref());
 
-    let custom_types = define_custom_types(metadata);
+    let (shared_types, custom_types): (Vec<_>, Vec<_>) = define_custom_types(metadata, skipped)?
+        .into_iter()
+        .partition(|(name, definition)| {
+            shared.map_or(false, |shared| shared.contains(name, definition))
+        });
 
-    let events = define_events(metadata);
+    let shared_imports = define_shared_imports(shared, &shared_types)?;
 
-    let traits = define_traits(metadata, trait_messages);
+    let custom_types = custom_types.into_iter().map(|(_, definition)| definition);
 
-    let impl_instance = define_impl_instance(metadata, top_level_messages);
+    let events = define_events(metadata, skipped)?;
 
-    quote! {
-        // This file was auto-generated with ink-wrapper (https://crates.io/crates/ink-wrapper).")
+    let event_signatures = define_event_signatures(contract, &events)?;
+
+    let events = events.into_iter().map(|(_, event)| event);
 
+    let traits = define_traits(metadata, trait_messages, options, skipped)?;
+
+    let impl_instance = define_impl_instance(metadata, top_level_messages, options, skipped)?;
+
+    Ok(quote! {
         use scale::Encode as _;
 
         #[allow(dead_code)]
         pub const CODE_HASH: [u8; 32] = [#(#code_hash),*];
 
+        #shared_imports
+
         #(#custom_types)*
 
         pub mod event {
-            #[allow(dead_code, clippy::large_enum_variant)]
+            #[allow(dead_code, clippy::large_enum_variant, clippy::enum_variant_names)]
             #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
             pub enum Event {
                 #(#events),*
             }
+
+            #event_signatures
         }
 
 
@@ -77,94 +183,405 @@ pub fn generate(
         #upload
 
         #impl_instance
+    })
+}
+
+/// Collects the items left out of the wrapper in lenient mode.
+struct Skipped {
+    lenient: bool,
+    errors: Vec<GenerateError>,
+}
+
+impl Skipped {
+    fn new(lenient: bool) -> Self {
+        Self {
+            lenient,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Returns the generated item, or fails if it couldn't be generated - unless in lenient mode, where the error is
+    /// recorded and `None` is returned instead.
+    fn check<T>(
+        &mut self,
+        item: Item,
+        result: Result<T, GenerateError>,
+    ) -> Result<Option<T>, GenerateError> {
+        match result {
+            Ok(generated) => Ok(Some(generated)),
+            Err(error) => {
+                let error = GenerateError::Item {
+                    item,
+                    error: Box::new(error),
+                };
+                if self.lenient {
+                    self.errors.push(error);
+                    Ok(None)
+                } else {
+                    Err(error)
+                }
+            }
+        }
     }
 }
 
+/// Generates the definitions of the custom types, along with the names they are defined with.
 fn define_custom_types(
     metadata: &InkProject,
-) -> impl Iterator<Item = proc_macro2::TokenStream> + '_ {
-    metadata
-        .registry()
-        .types
+    skipped: &mut Skipped,
+) -> Result<Vec<(String, proc_macro2::TokenStream)>, GenerateError> {
+    let mut defined = HashSet::new();
+    let mut types = Vec::new();
+
+    for typ in &metadata.registry().types {
+        // Every instance of a generic type is a separate entry in the registry, but it needs to be defined only once.
+        if !typ.ty.is_custom() || !defined.insert(&typ.ty.path.segments) {
+            continue;
+        }
+
+        let item = Item::Type {
+            id: typ.id,
+            path: typ.ty.path.segments.join("::"),
+        };
+        let definition = match skipped.check(item, define_type(&typ.ty, metadata))? {
+            Some(definition) => definition,
+            // Other items may still refer to the type, so a stub is defined in its place if possible.
+            None => match define_type_stub(&typ.ty, metadata) {
+                Ok(stub) => stub,
+                Err(_) => continue,
+            },
+        };
+        // Only composites and variants get a definition, and their names are a part of it, so they can't fail here.
+        if !definition.is_empty() {
+            types.push((type_name(&typ.ty, metadata)?.to_string(), definition));
+        }
+    }
+
+    Ok(types)
+}
+
+/// Generates the imports of the custom types defined in the shared module.
+fn define_shared_imports(
+    shared: Option<&SharedTypes>,
+    types: &[(String, proc_macro2::TokenStream)],
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let Some(shared) = shared.filter(|_| !types.is_empty()) else {
+        return Ok(quote! {});
+    };
+
+    let module = &shared.module;
+    let names = types
         .iter()
-        .filter(|typ| typ.ty.is_custom())
-        .map(|typ| define_type(&typ.ty, metadata))
+        .map(|(name, _)| ident(name))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(quote! {
+        pub use #module::{#(#names),*};
+    })
 }
 
-fn define_events(metadata: &InkProject) -> impl Iterator<Item = proc_macro2::TokenStream> + '_ {
-    metadata
-        .spec()
-        .events()
+/// Generates the variants of the `Event` enum, along with the index of the event each of them is for.
+fn define_events(
+    metadata: &InkProject,
+    skipped: &mut Skipped,
+) -> Result<Vec<(usize, proc_macro2::TokenStream)>, GenerateError> {
+    let mut events = Vec::new();
+
+    for (index, event) in metadata.spec().events().iter().enumerate() {
+        let item = Item::Event(event.label().to_string());
+        if let Some(event) = skipped.check(item, define_event(index, event, metadata))? {
+            events.push((index, event));
+        }
+    }
+
+    Ok(events)
+}
+
+/// Generates the signature topic constants of the events and the `EventDecode` implementation for the `Event` enum.
+///
+/// Only the events that made it into the `Event` enum are included.
+fn define_event_signatures(
+    metadata: &ContractMetadata,
+    generated: &[(usize, proc_macro2::TokenStream)],
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let events = metadata.project.spec().events();
+    let events = generated
         .iter()
-        .map(|event| define_event(event, metadata))
+        .map(|(index, _)| (&events[*index], metadata.event_signature_topics[*index]))
+        .collect::<Vec<_>>();
+
+    let signed = events
+        .iter()
+        .filter_map(|(event, topic)| topic.map(|topic| (*event, topic)))
+        .map(|(event, topic)| {
+            let name = format_ident!("{}_SIGNATURE_TOPIC", screaming_snake_case(event.label()));
+            (event, name, topic)
+        })
+        .collect::<Vec<_>>();
+
+    let definitions = signed.iter().map(|(event, name, topic)| {
+        let doc = format!("The signature topic of the `{}` event.", event.label());
+        quote! {
+            #[doc = #doc]
+            #[allow(dead_code)]
+            pub const #name: [u8; 32] = [#(#topic),*];
+        }
+    });
+    let labels = signed.iter().map(|(event, _, _)| event.label());
+    let consts = signed.iter().map(|(_, name, _)| name);
+
+    let decode_event = match metadata.version {
+        MetadataVersion::V4 => quote! {
+            fn decode_event(_topics: &[[u8; 32]], data: &[u8]) -> Result<Self, scale::Error> {
+                // ink! 4 prefixes the event data with the index of the event, so the topics are not needed.
+                <Self as scale::Decode>::decode(&mut &data[..])
+            }
+        },
+        MetadataVersion::V5 => {
+            let by_signature = signed
+                .iter()
+                .map(|(event, name, _)| {
+                    let decode = decode_event_fields(event, &metadata.project)?;
+                    Ok(quote! {
+                        if topics.first() == Some(&#name) {
+                            return #decode;
+                        }
+                    })
+                })
+                .collect::<Result<Vec<_>, GenerateError>>()?;
+            let anonymous = events
+                .iter()
+                .filter(|(_, topic)| topic.is_none())
+                .map(|(event, _)| {
+                    let decode = decode_event_fields(event, &metadata.project)?;
+                    Ok(quote! {
+                        if let Ok(event) = #decode {
+                            return Ok(event);
+                        }
+                    })
+                })
+                .collect::<Result<Vec<_>, GenerateError>>()?;
+
+            quote! {
+                fn decode_event(topics: &[[u8; 32]], data: &[u8]) -> Result<Self, scale::Error> {
+                    // ink! 5 only encodes the fields of the event, which is identified by its signature topic.
+                    #(#by_signature)*
+
+                    // Anonymous events have no signature topic, so the only option is to try decoding them in turn.
+                    #(#anonymous)*
+
+                    Err(scale::Error::from("Unknown event signature"))
+                }
+            }
+        }
+    };
+
+    Ok(quote! {
+        #(#definitions)*
+
+        impl ink_wrapper_types::EventDecode for Event {
+            const SIGNATURE_TOPICS: &'static [(&'static str, [u8; 32])] = &[#((#labels, #consts)),*];
+
+            #decode_event
+        }
+    })
 }
 
-fn define_traits(
+/// Generates an expression decoding the fields of an event (without the variant index) from `data`.
+fn decode_event_fields(
+    event: &EventSpec<PortableForm>,
     metadata: &InkProject,
-    trait_messages: HashMap<String, MessageList>,
-) -> Vec<proc_macro2::TokenStream> {
-    trait_messages
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let label = ident(event.label())?;
+    let names = event
+        .args()
         .iter()
-        .map(|(trait_name, messages)| define_trait(trait_name, messages, metadata))
-        .collect()
+        .map(|field| ident(field.label()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let types = event
+        .args()
+        .iter()
+        .map(|field| type_ref_prefix(field.ty().ty().id, metadata, "super"))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(quote! {
+        <(#(#types,)*) as scale::DecodeAll>::decode_all(&mut &data[..])
+            .map(|(#(#names,)*)| Self::#label { #(#names),* })
+    })
+}
+
+/// Converts a `CamelCase` name into `SCREAMING_SNAKE_CASE`.
+fn screaming_snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut result = String::new();
+
+    for (i, c) in chars.iter().enumerate() {
+        let prev = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1);
+        let word_start = c.is_uppercase()
+            && prev.map_or(false, |prev| {
+                prev.is_lowercase()
+                    || prev.is_ascii_digit()
+                    || (prev.is_uppercase() && next.map_or(false, |next| next.is_lowercase()))
+            });
+
+        if word_start {
+            result.push('_');
+        }
+        result.extend(c.to_uppercase());
+    }
+
+    result
+}
+
+fn define_traits(
+    metadata: &InkProject,
+    trait_messages: BTreeMap<String, MessageList>,
+    options: &Options,
+    skipped: &mut Skipped,
+) -> Result<Vec<proc_macro2::TokenStream>, GenerateError> {
+    let mut root = TraitModule::default();
+
+    for (trait_path, messages) in &trait_messages {
+        let mut segments = trait_path.split("::").collect::<Vec<_>>();
+        let trait_name = segments.pop().unwrap_or_default();
+
+        // An invalid name of the trait or of one of its modules makes all of its messages unusable.
+        let path = segments
+            .iter()
+            .map(|segment| ident(segment))
+            .collect::<Result<Vec<_>, _>>()
+            .and_then(|path| Ok((path, ident(trait_name)?)));
+        let (path, trait_name) = match path {
+            Ok(path) => path,
+            Err(error) => {
+                for message in messages {
+                    let item = Item::Message(message.label().to_string());
+                    skipped.check::<()>(item, Err(error.clone()))?;
+                }
+                continue;
+            }
+        };
+
+        let module = path.into_iter().fold(&mut root, |module, segment| {
+            module.modules.entry(segment).or_default()
+        });
+        module.traits.extend(define_trait(
+            trait_name, messages, metadata, options, skipped,
+        )?);
+    }
+
+    Ok(root.into_items())
+}
+
+/// The traits defined in a module, with nested modules mirroring the namespaces of traits like
+/// `psp22::extensions::metadata::PSP22Metadata`.
+#[derive(Default)]
+struct TraitModule {
+    traits: Vec<proc_macro2::TokenStream>,
+    modules: BTreeMap<Ident, TraitModule>,
+}
+
+impl TraitModule {
+    fn into_items(self) -> Vec<proc_macro2::TokenStream> {
+        let modules = self.modules.into_iter().map(|(name, module)| {
+            let items = module.into_items();
+            // The traits refer to `Instance` and the custom types by name, so everything from the parent is imported.
+            quote! {
+                #[allow(non_snake_case)]
+                pub mod #name {
+                    #[allow(unused_imports)]
+                    use super::*;
+
+                    #(#items)*
+                }
+            }
+        });
+
+        self.traits.into_iter().chain(modules).collect()
+    }
 }
 
 fn define_impl_instance(
     metadata: &InkProject,
     top_level_messages: Vec<&MessageSpec<PortableForm>>,
-) -> proc_macro2::TokenStream {
-    let constructors = metadata
-        .spec()
-        .constructors()
-        .iter()
-        .map(|constructor| define_constructor(constructor, metadata));
-    let messages = top_level_messages
-        .iter()
-        .map(|message| define_message(message, "pub", metadata));
+    options: &Options,
+    skipped: &mut Skipped,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let mut constructors = Vec::new();
+    for constructor in metadata.spec().constructors() {
+        let item = Item::Constructor(constructor.label().to_string());
+        constructors.extend(skipped.check(item, define_constructor(constructor, metadata))?);
+    }
 
-    quote! {
+    let mut messages = Vec::new();
+    for message in top_level_messages {
+        let item = Item::Message(message.label().to_string());
+        messages.extend(skipped.check(item, define_message(message, "pub", metadata, options))?);
+    }
+
+    Ok(quote! {
         impl Instance {
             #(#constructors)*
 
             #(#messages)*
         }
-    }
+    })
 }
 
-// If wasm_path is defined, returns a function that uploads the contract to the chain.
-// If `None`, returns empty `quote!{}` - a noop.
-fn define_upload(wasm_path: Option<String>) -> proc_macro2::TokenStream {
-    match wasm_path {
-        Some(wasm_path) => quote! {
-            #[allow(dead_code)]
-            pub fn upload() -> ink_wrapper_types::UploadCall
-            {
-                let wasm = include_bytes!(#wasm_path);
-                ink_wrapper_types::UploadCall::new(wasm.to_vec(), CODE_HASH)
-            }
-        },
-        None => quote! {},
+// If wasm_path is defined, returns a function that uploads the contract to the chain. Otherwise the WASM from a
+// `.contract` bundle is embedded in the function. If neither is available, returns empty `quote!{}` - a noop.
+fn define_upload(
+    wasm_path: Option<String>,
+    bundled_wasm: Option<&[u8]>,
+) -> proc_macro2::TokenStream {
+    let wasm = match (wasm_path, bundled_wasm) {
+        (Some(wasm_path), _) => quote! { include_bytes!(#wasm_path) },
+        (None, Some(wasm)) => {
+            let wasm = proc_macro2::Literal::byte_string(wasm);
+            quote! { #wasm }
+        }
+        (None, None) => return quote! {},
+    };
+
+    quote! {
+        #[allow(dead_code)]
+        pub fn upload() -> ink_wrapper_types::UploadCall
+        {
+            let wasm = #wasm;
+            ink_wrapper_types::UploadCall::new(wasm.to_vec(), CODE_HASH)
+        }
     }
 }
 
 /// Define a group of messages with a common prefix (e.g. `PSP22::`).
 ///
-/// These messages will be grouped into a trait and implemented for the contract to avoid name clashes.
-fn define_trait<'a, 'b>(
-    trait_name: &str,
-    messages: &[&'b MessageSpec<PortableForm>],
-    metadata: &'a InkProject,
-) -> proc_macro2::TokenStream
-where
-    'a: 'b,
-{
-    let trait_name = format_ident!("{}", trait_name);
-    let trait_messages = messages
-        .iter()
-        .map(|m| define_message_head(m, "", metadata));
+/// These messages will be grouped into a trait and implemented for the contract to avoid name clashes. Returns `None`
+/// if none of the messages could be generated in lenient mode.
+fn define_trait(
+    trait_name: Ident,
+    messages: &[&MessageSpec<PortableForm>],
+    metadata: &InkProject,
+    options: &Options,
+    skipped: &mut Skipped,
+) -> Result<Option<proc_macro2::TokenStream>, GenerateError> {
+    let mut trait_messages = Vec::new();
+    let mut impl_messages = Vec::new();
+
+    for message in messages {
+        let item = Item::Message(message.label().to_string());
+        let generated = define_message_head(message, "", metadata, options)
+            .and_then(|head| Ok((head, define_message(message, "", metadata, options)?)));
+        if let Some((head, message)) = skipped.check(item, generated)? {
+            trait_messages.push(head);
+            impl_messages.push(message);
+        }
+    }
 
-    let impl_messages = messages.iter().map(|m| define_message(m, "", metadata));
-    quote! {
+    if trait_messages.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(quote! {
         pub trait #trait_name {
             #(#trait_messages;)*
         }
@@ -172,27 +589,28 @@ where
         impl #trait_name for Instance {
             #(#impl_messages)*
         }
-    }
+    }))
 }
 
 fn define_message_head(
     message: &MessageSpec<PortableForm>,
     visibility: &str,
     metadata: &InkProject,
-) -> proc_macro2::TokenStream {
+    options: &Options,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
     if message.mutates() {
-        define_mutator_head(message, visibility, metadata)
+        define_mutator_head(message, visibility, metadata, options)
     } else {
-        define_reader_head(message, visibility, metadata)
+        define_reader_head(message, visibility, metadata, options)
     }
 }
 
 /// Group messages by their "trait" prefix (for example groups all messages with a `PSP22::` prefix together).
 ///
 /// Returns the "main" group without any prefix as a special group (first member of the result pair).
-fn group_messages(metadata: &InkProject) -> (MessageList, HashMap<String, MessageList>) {
+fn group_messages(metadata: &InkProject) -> (MessageList, BTreeMap<String, MessageList>) {
     let mut top_level_messages = Vec::new();
-    let mut trait_messages = HashMap::new();
+    let mut trait_messages = BTreeMap::new();
 
     for message in metadata.spec().messages() {
         match message.trait_name() {
@@ -210,47 +628,100 @@ fn group_messages(metadata: &InkProject) -> (MessageList, HashMap<String, Messag
 }
 
 /// Generates a type definition for a custom type used in the contract.
-fn define_type(typ: &Type<PortableForm>, metadata: &InkProject) -> proc_macro2::TokenStream {
+fn define_type(
+    typ: &Type<PortableForm>,
+    metadata: &InkProject,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
     match &typ.type_def {
         TypeDef::Variant(variant) => define_enum(typ, variant, metadata),
         TypeDef::Composite(composite) => define_composite(typ, composite, metadata),
-        _ => quote! {},
+        _ => Ok(quote! {}),
     }
 }
 
+/// Generates a stand-in for a custom type whose definition couldn't be generated in lenient mode.
+///
+/// The stub can be referred to like the original type, but it can't be constructed and it always fails to decode.
+fn define_type_stub(
+    typ: &Type<PortableForm>,
+    metadata: &InkProject,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let params = type_params(typ)?;
+    let generics = quote_type_params(&params);
+    let param_names = params.iter().map(|(_, name)| name);
+    let name = type_name(typ, metadata)?;
+    let doc = format!(
+        "A stub for `{}`, which ink-wrapper couldn't generate.",
+        typ.path.segments.join("::")
+    );
+    let error = format!(
+        "Decoding `{}` is not supported",
+        typ.path.segments.join("::")
+    );
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[allow(dead_code)]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct #name #generics (core::marker::PhantomData<(#(#param_names,)*)>);
+
+        impl #generics scale::Encode for #name #generics {
+            fn encode_to<O: scale::Output + ?Sized>(&self, _dest: &mut O) {}
+        }
+
+        impl #generics scale::Decode for #name #generics {
+            fn decode<I: scale::Input>(_input: &mut I) -> Result<Self, scale::Error> {
+                Err(scale::Error::from(#error))
+            }
+        }
+    })
+}
+
 fn named_variant(
     name: &str,
-    fields: &[(String, u32)],
+    fields: &[(String, FieldType)],
+    params: &TypeParams,
     metadata: &InkProject,
-) -> proc_macro2::TokenStream {
-    let fields = fields.iter().map(|(name, typ)| {
-        let typ = type_ref(*typ, metadata);
-        let name = format_ident!("{}", name);
-        quote! {
-            #name: #typ
-        }
-    });
-    let name = format_ident!("{}", name);
-    quote! {
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let fields = fields
+        .iter()
+        .map(|(name, typ)| {
+            let typ = field_type_ref(typ, params, metadata)?;
+            let name = ident(name)?;
+            Ok(quote! {
+                #name: #typ
+            })
+        })
+        .collect::<Result<Vec<_>, GenerateError>>()?;
+    let name = ident(name)?;
+    Ok(quote! {
         #name {
             #(#fields),*
         }
-    }
+    })
 }
 
-fn unnamed_variant(name: &str, fields: &[u32], metadata: &InkProject) -> proc_macro2::TokenStream {
-    let fields = fields.iter().map(|typ| {
-        let typ = type_ref(*typ, metadata);
-        quote! {
-            #typ
-        }
-    });
-    let name = format_ident!("{}", name);
-    quote! {
+fn unnamed_variant(
+    name: &str,
+    fields: &[FieldType],
+    params: &TypeParams,
+    metadata: &InkProject,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let fields = fields
+        .iter()
+        .map(|typ| {
+            let typ = field_type_ref(typ, params, metadata)?;
+            Ok(quote! {
+                #typ
+            })
+        })
+        .collect::<Result<Vec<_>, GenerateError>>()?;
+    let name = ident(name)?;
+    Ok(quote! {
         #name (
             #(#fields),*
         )
-    }
+    })
 }
 
 /// Generates a type definition for an enum.
@@ -258,21 +729,24 @@ fn define_enum(
     typ: &Type<PortableForm>,
     variant: &TypeDefVariant<PortableForm>,
     metadata: &InkProject,
-) -> proc_macro2::TokenStream {
-    let typ = typ.qualified_name();
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let params = type_params(typ)?;
+    let generics = quote_type_params(&params);
+    let typ = type_name(typ, metadata)?;
     let variants = variant
         .variants
         .iter()
-        .map(|variant| match variant.aggregate_fields() {
-            Fields::Named(fields) => named_variant(&variant.name, &fields, metadata),
-            Fields::Unnamed(fields) => unnamed_variant(&variant.name, &fields, metadata),
-        });
-    quote! {
+        .map(|variant| match variant.aggregate_fields()? {
+            Fields::Named(fields) => named_variant(&variant.name, &fields, &params, metadata),
+            Fields::Unnamed(fields) => unnamed_variant(&variant.name, &fields, &params, metadata),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(quote! {
         #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
-        pub enum #typ {
+        pub enum #typ #generics {
             #(#variants),*
         }
-    }
+    })
 }
 
 /// Generates a type definition for a struct.
@@ -280,39 +754,194 @@ fn define_composite(
     typ: &Type<PortableForm>,
     composite: &TypeDefComposite<PortableForm>,
     metadata: &InkProject,
-) -> proc_macro2::TokenStream {
-    match composite.aggregate_fields() {
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let params = type_params(typ)?;
+    let generics = quote_type_params(&params);
+    match composite.aggregate_fields()? {
         Fields::Named(fields) => {
-            let typ = typ.qualified_name();
-            let fields = fields.iter().map(|(name, typ)| {
-                let typ = type_ref(*typ, metadata);
-                let name = format_ident!("{}", name);
-                quote! {
-                    pub #name: #typ
-                }
-            });
-            quote! {
+            let typ = type_name(typ, metadata)?;
+            let fields = fields
+                .iter()
+                .map(|(name, typ)| {
+                    let typ = field_type_ref(typ, &params, metadata)?;
+                    let name = ident(name)?;
+                    Ok(quote! {
+                        pub #name: #typ
+                    })
+                })
+                .collect::<Result<Vec<_>, GenerateError>>()?;
+            Ok(quote! {
                 #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
-                pub struct #typ {
+                pub struct #typ #generics {
                     #(#fields),*
                 }
-            }
+            })
         }
         Fields::Unnamed(unnamed) => {
-            let typ = typ.qualified_name();
-            let fields = unnamed.iter().map(|typ| {
-                let typ = type_ref(*typ, metadata);
-                quote! {
-                    pub #typ
-                }
-            });
-            quote! {
+            let typ = type_name(typ, metadata)?;
+            let fields = unnamed
+                .iter()
+                .map(|typ| {
+                    let typ = field_type_ref(typ, &params, metadata)?;
+                    Ok(quote! {
+                        pub #typ
+                    })
+                })
+                .collect::<Result<Vec<_>, GenerateError>>()?;
+            Ok(quote! {
                 #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
-                pub struct #typ (
+                pub struct #typ #generics (
                     #(#fields),*
                 );
+            })
+        }
+    }
+}
+
+/// The type parameters of a generic type, along with the IDs of the types they are bound to in the registry entry the
+/// type is defined from. See `field_type_ref` for how they are told apart.
+type TypeParams = [(u32, Ident)];
+
+/// Returns the type parameters of a type. Parameters without a type (skipped in the metadata) are left out, both here
+/// and where the type is referenced.
+fn type_params(typ: &Type<PortableForm>) -> Result<Vec<(u32, Ident)>, GenerateError> {
+    typ.type_params
+        .iter()
+        .filter_map(|param| Some((param.ty?.id, &param.name)))
+        .map(|(id, name)| Ok((id, ident(name)?)))
+        .collect()
+}
+
+fn quote_type_params(params: &TypeParams) -> proc_macro2::TokenStream {
+    if params.is_empty() {
+        quote! {}
+    } else {
+        let params = params.iter().map(|(_, name)| name);
+        quote! { <#(#params),*> }
+    }
+}
+
+/// Generates a type reference to the type of a field in the definition of a (potentially generic) type.
+///
+/// The type parameters are recognized by their names in the type of the field as written in the contract, which is
+/// walked along with the type it resolves to. Several parameters can be bound to the same type (e.g. `Either<u32, u32>`),
+/// so the types they are bound to only tell them apart where the written type can't be matched with the resolved one,
+/// e.g. behind a type alias.
+fn field_type_ref(
+    field: &FieldType,
+    params: &TypeParams,
+    metadata: &InkProject,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let written = field
+        .type_name
+        .as_ref()
+        .and_then(|type_name| syn::parse_str::<syn::Type>(type_name).ok());
+
+    match written {
+        Some(written) => type_ref_written(field.id, &written, params, metadata),
+        None => type_ref(field.id, metadata),
+    }
+}
+
+/// Generates a type reference to the type with the given ID, using the type parameters where `written` names them.
+fn type_ref_written(
+    id: u32,
+    written: &syn::Type,
+    params: &TypeParams,
+    metadata: &InkProject,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let mentioned = mentioned_params(written, params);
+    if mentioned.is_empty() {
+        return type_ref(id, metadata);
+    }
+    if let syn::Type::Path(path) = written {
+        if let Some((_, param)) = path
+            .path
+            .get_ident()
+            .and_then(|name| params.iter().find(|(_, param)| param == name))
+        {
+            return Ok(quote! { #param });
+        }
+    }
+
+    let typ = resolve(metadata, id)?;
+    let args = written_type_args(written);
+
+    match (&typ.type_def, written) {
+        (TypeDef::Tuple(tuple), syn::Type::Tuple(written))
+            if tuple.fields.len() == written.elems.len() =>
+        {
+            let typs = tuple
+                .fields
+                .iter()
+                .zip(&written.elems)
+                .map(|(t, written)| type_ref_written(t.id, written, params, metadata))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(quote! {
+                (#(#typs),*)
+            })
+        }
+        (TypeDef::Array(array), syn::Type::Array(written)) => {
+            let typ = type_ref_written(array.type_param.id, &written.elem, params, metadata)?;
+            let len = array.len as usize;
+            Ok(quote! {
+                [ #typ ; #len ]
+            })
+        }
+        (TypeDef::Sequence(sequence), _) if args.len() == 1 => {
+            let typ = type_ref_written(sequence.type_param.id, args[0], params, metadata)?;
+            Ok(quote! {
+                Vec<#typ>
+            })
+        }
+        (TypeDef::Composite(_) | TypeDef::Variant(_), _) if args.len() == typ.type_params.len() => {
+            let generics = typ
+                .type_params
+                .iter()
+                .zip(args)
+                .filter_map(|(param, written)| Some((param.ty?, written)))
+                .map(|(param, written)| type_ref_written(param.id, written, params, metadata))
+                .collect::<Result<Vec<_>, _>>()?;
+            let name = type_name(typ, metadata)?;
+            if generics.is_empty() {
+                Ok(quote! { #name })
+            } else {
+                Ok(quote! { #name <#(#generics),*> })
             }
         }
+        _ => type_ref_params(id, metadata, "", &mentioned),
+    }
+}
+
+/// Returns the type parameters named in a type.
+fn mentioned_params(written: &syn::Type, params: &TypeParams) -> Vec<(u32, Ident)> {
+    let written = written.to_token_stream().to_string();
+    let words = written
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .collect::<HashSet<_>>();
+
+    params
+        .iter()
+        .filter(|(_, param)| words.contains(param.to_string().as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Returns the type arguments of the last segment of a path type, e.g. `T` in `ink::prelude::vec::Vec<T>`.
+fn written_type_args(written: &syn::Type) -> Vec<&syn::Type> {
+    let syn::Type::Path(path) = written else {
+        return Vec::new();
+    };
+    match path.path.segments.last().map(|segment| &segment.arguments) {
+        Some(syn::PathArguments::AngleBracketed(args)) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::GenericArgument::Type(typ) => Some(typ),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
     }
 }
 
@@ -320,17 +949,17 @@ fn define_composite(
 fn define_constructor(
     constructor: &ConstructorSpec<PortableForm>,
     metadata: &InkProject,
-) -> proc_macro2::TokenStream {
+) -> Result<proc_macro2::TokenStream, GenerateError> {
     let data_ident = &new_name("data", constructor.args());
     let docs = quote_docs(constructor.docs());
-    let label = format_ident!("{}", constructor.label());
-    let args = message_args(constructor.args(), metadata);
+    let label = ident(constructor.label())?;
+    let args = message_args(constructor.args(), metadata)?;
     let ret_res = if *constructor.payable() {
         quote! { ink_wrapper_types::InstantiateCallNeedsValue<Self> }
     } else {
         quote! { ink_wrapper_types::InstantiateCall<Self> }
     };
-    let data = gather_args(constructor.selector().to_bytes(), constructor.args());
+    let data = gather_args(constructor.selector().to_bytes(), constructor.args())?;
     let body = if *constructor.payable() {
         quote! {
             let #data_ident = #data;
@@ -343,13 +972,13 @@ fn define_constructor(
         }
     };
 
-    quote! {
+    Ok(quote! {
         #docs
-        #[allow(dead_code, clippy::too_many_arguments)]
+        #[allow(dead_code, clippy::too_many_arguments, clippy::type_complexity)]
         pub fn #label ( #args ) -> #ret_res {
             #body
         }
-    }
+    })
 }
 
 /// Generates a function wrapping a contract message send.
@@ -357,11 +986,12 @@ fn define_message(
     message: &MessageSpec<PortableForm>,
     visibility: &str,
     metadata: &InkProject,
-) -> proc_macro2::TokenStream {
+    options: &Options,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
     if message.mutates() {
-        define_mutator(message, visibility, metadata)
+        define_mutator(message, visibility, metadata, options)
     } else {
-        define_reader(message, visibility, metadata)
+        define_reader(message, visibility, metadata, options)
     }
 }
 
@@ -370,21 +1000,31 @@ fn define_reader(
     message: &MessageSpec<PortableForm>,
     visibility: &str,
     metadata: &InkProject,
-) -> proc_macro2::TokenStream {
+    options: &Options,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
     let data_ident = &new_name("data", message.args());
     let docs = quote_docs(message.docs());
-    let reader_head = define_reader_head(message, visibility, metadata);
-    let args = gather_args(message.selector().to_bytes(), message.args());
+    let reader_head = define_reader_head(message, visibility, metadata, options)?;
+    let args = gather_args(message.selector().to_bytes(), message.args())?;
+    let res = if message.payable() {
+        quote! {
+            ink_wrapper_types::ReadCallNeedsValue::new(self.account_id, #data_ident)
+        }
+    } else {
+        quote! {
+            ink_wrapper_types::ReadCall::new(self.account_id, #data_ident)
+        }
+    };
 
-    quote! {
+    Ok(quote! {
         #docs
-        #[allow(dead_code, clippy::too_many_arguments)]
+        #[allow(dead_code, clippy::too_many_arguments, clippy::type_complexity)]
         #reader_head
         {
             let #data_ident = #args;
-            ink_wrapper_types::ReadCall::new(self.account_id, #data_ident)
+            #res
         }
-    }
+    })
 }
 
 fn quote_visibility(visibility: &str) -> proc_macro2::TokenStream {
@@ -400,19 +1040,20 @@ fn define_reader_head(
     message: &MessageSpec<PortableForm>,
     visibility: &str,
     metadata: &InkProject,
-) -> proc_macro2::TokenStream {
-    let method = format_ident!("{}", message.method_name());
-    let args = message_args(message.args(), metadata);
-    let read_call_type = type_ref(message.return_type().opt_type().unwrap().ty().id, metadata);
+    options: &Options,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let method = ident(&message.method_name())?;
+    let args = message_args(message.args(), metadata)?;
+    let read_call_type = message_return_type(message, metadata, options)?;
     let ret_type = if message.payable() {
         quote! { ink_wrapper_types::ReadCallNeedsValue<#read_call_type> }
     } else {
         quote! { ink_wrapper_types::ReadCall<#read_call_type> }
     };
     let visibility = quote_visibility(visibility);
-    quote! {
+    Ok(quote! {
         #visibility fn #method (&self, #args) -> #ret_type
-    }
+    })
 }
 
 /// Generates a function wrapping a contract message that mutates the contract.
@@ -420,73 +1061,167 @@ fn define_mutator(
     message: &MessageSpec<PortableForm>,
     visibility: &str,
     metadata: &InkProject,
-) -> proc_macro2::TokenStream {
+    options: &Options,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
     let data_ident = &new_name("data", message.args());
-    let data = gather_args(message.selector().to_bytes(), message.args());
+    let data = gather_args(message.selector().to_bytes(), message.args())?;
     let docs = quote_docs(message.docs());
-    let mutator_head = define_mutator_head(message, visibility, metadata);
+    let mutator_head = define_mutator_head(message, visibility, metadata, options)?;
+    let with_error_type = match message_error_type(return_type(message)?, metadata)? {
+        Some(error) => quote! { .with_error_type::<#error>() },
+        None => quote! {},
+    };
     let res = if message.payable() {
         quote! {
-            ink_wrapper_types::ExecCallNeedsValue::new(self.account_id, #data_ident)
+            ink_wrapper_types::ExecCallNeedsValue::new(self.account_id, #data_ident) #with_error_type
         }
     } else {
         quote! {
-            ink_wrapper_types::ExecCall::new(self.account_id, #data_ident)
+            ink_wrapper_types::ExecCall::new(self.account_id, #data_ident) #with_error_type
         }
     };
-    quote! {
+    Ok(quote! {
         #docs
-        #[allow(dead_code, clippy::too_many_arguments)]
+        #[allow(dead_code, clippy::too_many_arguments, clippy::type_complexity)]
         #mutator_head
         {
             let #data_ident = #data;
             #res
         }
-    }
+    })
 }
 
 fn define_mutator_head(
     message: &MessageSpec<PortableForm>,
     visibility: &str,
     metadata: &InkProject,
-) -> proc_macro2::TokenStream {
-    let method = format_ident!("{}", message.method_name());
-    let message_args = message_args(message.args(), metadata);
-    let exec_call_type = type_ref(message.return_type().opt_type().unwrap().ty().id, metadata);
+    options: &Options,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let method = ident(&message.method_name())?;
+    let message_args = message_args(message.args(), metadata)?;
+    let exec_call_type = message_return_type(message, metadata, options)?;
     let ret_type = if message.payable() {
         quote! { ink_wrapper_types::ExecCallNeedsValue<#exec_call_type> }
     } else {
         quote! { ink_wrapper_types::ExecCall<#exec_call_type> }
     };
     let visibility = quote_visibility(visibility);
-    quote! {
+    Ok(quote! {
         #visibility fn #method (&self, #message_args) -> #ret_type
+    })
+}
+
+/// Generates the type the result of a message is decoded into.
+fn message_return_type(
+    message: &MessageSpec<PortableForm>,
+    metadata: &InkProject,
+    options: &Options,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let id = return_type(message)?;
+
+    if options.flatten_results {
+        if let Some(flat) = flat_return_type(id, metadata)? {
+            return Ok(flat);
+        }
+    }
+
+    type_ref(id, metadata)
+}
+
+/// Generates the flattened counterpart of the type returned by a message: `FlatResult<T, E>` for
+/// `Result<Result<T, E>, LangError>` and `Flat<T>` for `Result<T, LangError>`. Returns `None` for other types, though
+/// ink! always wraps the value returned by a message in a `Result` with `LangError`.
+fn flat_return_type(
+    id: u32,
+    metadata: &InkProject,
+) -> Result<Option<proc_macro2::TokenStream>, GenerateError> {
+    let Some((ok, err)) = result_params(resolve(metadata, id)?) else {
+        return Ok(None);
+    };
+    if !resolve(metadata, err)?.is_lang_error() {
+        return Ok(None);
+    }
+
+    match result_params(resolve(metadata, ok)?) {
+        Some((value, error)) => {
+            let value = type_ref(value, metadata)?;
+            let error = type_ref(error, metadata)?;
+            Ok(Some(
+                quote! { ink_wrapper_types::FlatResult<#value, #error> },
+            ))
+        }
+        None => {
+            let value = type_ref(ok, metadata)?;
+            Ok(Some(quote! { ink_wrapper_types::Flat<#value> }))
+        }
+    }
+}
+
+/// Generates the error type of a message returning `Result<T, E>`, which ink! wraps in `Result<_, LangError>`.
+fn message_error_type(
+    id: u32,
+    metadata: &InkProject,
+) -> Result<Option<proc_macro2::TokenStream>, GenerateError> {
+    let Some((ok, err)) = result_params(resolve(metadata, id)?) else {
+        return Ok(None);
+    };
+    if !resolve(metadata, err)?.is_lang_error() {
+        return Ok(None);
+    }
+
+    match result_params(resolve(metadata, ok)?) {
+        Some((_, error)) => Ok(Some(type_ref(error, metadata)?)),
+        None => Ok(None),
     }
 }
 
+/// Returns the IDs of the `Ok` and `Err` types if the type is a `Result`.
+fn result_params(typ: &Type<PortableForm>) -> Option<(u32, u32)> {
+    if typ.path.segments != ["Result"] {
+        return None;
+    }
+
+    match typ.type_params.as_slice() {
+        [ok, err] => Some((ok.ty?.id, err.ty?.id)),
+        _ => None,
+    }
+}
+
+/// Returns the ID of the type returned by a message. ink! always declares it, even for messages returning nothing.
+fn return_type(message: &MessageSpec<PortableForm>) -> Result<u32, GenerateError> {
+    message
+        .return_type()
+        .opt_type()
+        .map(|typ| typ.ty().id)
+        .ok_or(GenerateError::MissingReturnType)
+}
+
 /// Generates a block of statements that pack the selector and arguments into a SCALE encoded vector of bytes.
 ///
 /// The intention is to assign the result to a variable.
 fn gather_args(
     selector: &[u8],
     args: &[MessageParamSpec<PortableForm>],
-) -> proc_macro2::TokenStream {
+) -> Result<proc_macro2::TokenStream, GenerateError> {
     let selector_deref: Vec<u8> = selector.to_vec();
     if args.is_empty() {
-        quote! {
+        Ok(quote! {
             vec![#(#selector_deref),*]
-        }
+        })
     } else {
-        let data_ident = format_ident!("{}", new_name("data", args));
-        let args = args.iter().map(|arg| {
-            let arg_label = format_ident!("{}", arg.label());
-            quote! { #arg_label.encode_to(&mut #data_ident) }
-        });
-        quote!({
+        let data_ident = new_name("data", args);
+        let args = args
+            .iter()
+            .map(|arg| {
+                let arg_label = ident(arg.label())?;
+                Ok(quote! { #arg_label.encode_to(&mut #data_ident) })
+            })
+            .collect::<Result<Vec<_>, GenerateError>>()?;
+        Ok(quote!({
             let mut #data_ident = vec![#(#selector_deref),*];
             #(#args;)*
             #data_ident
-        })
+        }))
     }
 }
 
@@ -494,65 +1229,96 @@ fn gather_args(
 fn message_args(
     args: &[MessageParamSpec<PortableForm>],
     metadata: &InkProject,
-) -> proc_macro2::TokenStream {
-    let args = args.iter().map(|arg| {
-        let arg_label = format_ident!("{}", arg.label());
-        let arg_type = type_ref(arg.ty().ty().id, metadata);
-        quote! { #arg_label: #arg_type }
-    });
-    quote! { #(#args),* }
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let args = args
+        .iter()
+        .map(|arg| {
+            let arg_label = ident(arg.label())?;
+            let arg_type = type_ref(arg.ty().ty().id, metadata)?;
+            Ok(quote! { #arg_label: #arg_type })
+        })
+        .collect::<Result<Vec<_>, GenerateError>>()?;
+    Ok(quote! { #(#args),* })
 }
 
 /// Generates an event definition as a variant in the `Event` enum.
 ///
 /// Note that these definitions are hidden in a module to avoid name clashes (just in case someone uses `Event` as a
-/// type name), so references to types defined in the contract need to be prefixed with `super::`.
+/// type name), so references to types defined in the contract need to be prefixed with `super::`. The index of the
+/// variant is explicit, so that events left out in lenient mode don't shift the ones after them.
 fn define_event(
+    index: usize,
     event: &EventSpec<PortableForm>,
     metadata: &InkProject,
-) -> proc_macro2::TokenStream {
+) -> Result<proc_macro2::TokenStream, GenerateError> {
     let event_docs = quote_docs(event.docs());
-    let event_label = format_ident!("{}", event.label());
-    let event_fields = event.args().iter().map(|field| {
-        let field_docs = quote_docs(field.docs());
-        let field_label = format_ident!("{}", field.label());
-        let field_type = type_ref_prefix(field.ty().ty().id, metadata, "super");
-        quote! {
-           #field_docs
-           #field_label: #field_type
-        }
-    });
-    quote! {
+    let event_label = ident(event.label())?;
+    let index = u8::try_from(index).map_err(|_| GenerateError::TooManyEvents)?;
+    let index = proc_macro2::Literal::u8_unsuffixed(index);
+    let event_fields = event
+        .args()
+        .iter()
+        .map(|field| {
+            let field_docs = quote_docs(field.docs());
+            let field_label = ident(field.label())?;
+            let field_type = type_ref_prefix(field.ty().ty().id, metadata, "super")?;
+            Ok(quote! {
+               #field_docs
+               #field_label: #field_type
+            })
+        })
+        .collect::<Result<Vec<_>, GenerateError>>()?;
+    Ok(quote! {
         #event_docs
+        #[codec(index = #index)]
         #event_label {
             #(#event_fields),*
         }
-    }
+    })
 }
 
 /// Generates a type reference to the given type (for example to use as an argument type, return type, etc.).
-fn type_ref(id: u32, metadata: &InkProject) -> proc_macro2::TokenStream {
+fn type_ref(id: u32, metadata: &InkProject) -> Result<proc_macro2::TokenStream, GenerateError> {
     type_ref_prefix(id, metadata, "")
 }
 
 /// Generates a type reference to the given type (for example to use as an argument type, return type, etc.).
 ///
 /// The `prefix` is prepended to the type name if the type is a custom type.
-fn type_ref_prefix(id: u32, metadata: &InkProject, prefix: &str) -> proc_macro2::TokenStream {
-    let typ = resolve(metadata, id);
+fn type_ref_prefix(
+    id: u32,
+    metadata: &InkProject,
+    prefix: &str,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    type_ref_params(id, metadata, prefix, &[])
+}
+
+/// Generates a type reference to the given type inside the definition of a generic type, replacing the types the type
+/// parameters are bound to with the parameters themselves.
+fn type_ref_params(
+    id: u32,
+    metadata: &InkProject,
+    prefix: &str,
+    params: &TypeParams,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    if let Some((_, param)) = params.iter().find(|(param_id, _)| *param_id == id) {
+        return Ok(quote! { #param });
+    }
+
+    let typ = resolve(metadata, id)?;
 
     match &typ.type_def {
         TypeDef::Primitive(primitive) => {
             let t = type_ref_primitive(primitive);
-            quote! { #t }
+            Ok(quote! { #t })
         }
-        TypeDef::Tuple(tuple) => type_ref_tuple(tuple, metadata, prefix),
-        TypeDef::Composite(_) => type_ref_generic(typ, metadata, prefix),
-        TypeDef::Variant(_) => type_ref_generic(typ, metadata, prefix),
-        TypeDef::Array(array) => type_ref_array(array, metadata, prefix),
-        TypeDef::Sequence(sequence) => type_ref_sequence(sequence, metadata, prefix),
-        TypeDef::Compact(compact) => type_ref_compact(compact, metadata, prefix),
-        TypeDef::BitSequence(_) => panic!("Bit sequences are not supported yet."),
+        TypeDef::Tuple(tuple) => type_ref_tuple(tuple, metadata, prefix, params),
+        TypeDef::Composite(_) => type_ref_generic(typ, metadata, prefix, params),
+        TypeDef::Variant(_) => type_ref_generic(typ, metadata, prefix, params),
+        TypeDef::Array(array) => type_ref_array(array, metadata, prefix, params),
+        TypeDef::Sequence(sequence) => type_ref_sequence(sequence, metadata, prefix, params),
+        TypeDef::Compact(compact) => type_ref_compact(compact, metadata, prefix, params),
+        TypeDef::BitSequence(bit_sequence) => type_ref_bit_sequence(bit_sequence, metadata),
     }
 }
 
@@ -561,15 +1327,17 @@ fn type_ref_generic(
     typ: &Type<PortableForm>,
     metadata: &InkProject,
     prefix: &str,
-) -> proc_macro2::TokenStream {
+    params: &TypeParams,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
     let generics = if typ.type_params.is_empty() {
         quote! {}
     } else {
-        let generics = typ.type_params.iter().map(|param| {
-            let param = param.ty.unwrap();
-            let param = type_ref_prefix(param.id, metadata, prefix);
-            quote! { #param }
-        });
+        let generics = typ
+            .type_params
+            .iter()
+            .filter_map(|param| param.ty)
+            .map(|param| type_ref_params(param.id, metadata, prefix, params))
+            .collect::<Result<Vec<_>, _>>()?;
         quote! { <#(#generics),*> }
     };
 
@@ -580,8 +1348,128 @@ fn type_ref_generic(
         quote! { #prefix_ident:: }
     };
 
-    let qualified_name = typ.qualified_name();
-    quote! { #prefix #qualified_name #generics }
+    let qualified_name = type_name(typ, metadata)?;
+    Ok(quote! { #prefix #qualified_name #generics })
+}
+
+/// Returns the name by which the type can be referenced, like [TypeExtensions::qualified_name].
+///
+/// Custom types are all defined in the same module, so when several of them share a name (e.g. `my_contract::Error`
+/// and `my_contract::erc20::Error`), the modules that tell them apart are prepended to the name (`Error` and
+/// `Erc20Error`).
+fn type_name(
+    typ: &Type<PortableForm>,
+    metadata: &InkProject,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    if !typ.is_custom() {
+        return typ.qualified_name();
+    }
+
+    let segments = &typ.path.segments;
+    let name = segments
+        .last()
+        .ok_or_else(|| GenerateError::InvalidIdentifier(String::new()))?;
+    let candidates = mangled_candidates(segments, metadata);
+    if candidates.is_empty() {
+        return typ.qualified_name();
+    }
+
+    // The mangled name may be taken by another type (e.g. `nested::Struct1` next to a `NestedStruct1`), or be one that
+    // another type could be mangled into (e.g. `b_c::Error` and `b::c::Error` both into `BCError`). In that case more
+    // modules are prepended, and if that's not enough, the index of the type in the registry is appended.
+    let taken = |candidate: &String| {
+        candidate != name
+            && metadata.registry().types.iter().any(|other| {
+                let custom = other.ty.is_custom();
+                let other = &other.ty.path.segments;
+                custom
+                    && other != segments
+                    && other
+                        .last()
+                        .is_some_and(|other_name| candidate.ends_with(other_name))
+                    && match mangled_candidates(other, metadata) {
+                        others if others.is_empty() => other.last() == Some(candidate),
+                        others => others.contains(candidate),
+                    }
+            })
+    };
+    let mangled = candidates.into_iter().find(|candidate| !taken(candidate));
+    let mangled = match mangled {
+        Some(mangled) => mangled,
+        None => {
+            let index = metadata
+                .registry()
+                .types
+                .iter()
+                .position(|other| other.ty.path.segments == *segments)
+                .unwrap_or_default();
+            format!("{}{index}", upper_camel_case(&segments.join("_")))
+        }
+    };
+
+    let mangled = ident(&mangled)?;
+    Ok(quote! { #mangled })
+}
+
+/// Returns the names a custom type can be mangled into, shortest first, or nothing if no other type has the same name.
+///
+/// The names are made by prepending the modules of the type's path, starting with the first one that tells it apart from
+/// the types with the same name.
+fn mangled_candidates(segments: &[String], metadata: &InkProject) -> Vec<String> {
+    let Some(name) = segments.last() else {
+        return Vec::new();
+    };
+    let clashing = metadata
+        .registry()
+        .types
+        .iter()
+        .map(|other| &other.ty)
+        .filter(|other| other.is_custom() && other.path.segments.last() == Some(name))
+        .map(|other| &other.path.segments)
+        .filter(|other| *other != segments)
+        .collect::<Vec<_>>();
+
+    if clashing.is_empty() {
+        return Vec::new();
+    }
+
+    // The modules shared by all the clashing types don't tell them apart, so they are skipped.
+    let common = clashing
+        .iter()
+        .map(|other| {
+            segments
+                .iter()
+                .zip(other.iter())
+                .take(segments.len().min(other.len()) - 1)
+                .take_while(|(a, b)| a == b)
+                .count()
+        })
+        .min()
+        .unwrap_or(0);
+
+    (0..=common)
+        .rev()
+        .map(|common| {
+            segments[common..segments.len() - 1]
+                .iter()
+                .map(|module| upper_camel_case(module))
+                .chain(std::iter::once(name.clone()))
+                .collect::<String>()
+        })
+        .collect()
+}
+
+/// Converts a `snake_case` module name into `UpperCamelCase`.
+fn upper_camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
 }
 
 /// Generates a type reference to a primitive type.
@@ -597,8 +1485,8 @@ fn type_ref_primitive(primitive: &TypeDefPrimitive) -> proc_macro2::TokenStream
         TypeDefPrimitive::I64 => quote! { i64 },
         TypeDefPrimitive::I128 => quote! { i128 },
         TypeDefPrimitive::U128 => quote! { u128 },
-        TypeDefPrimitive::U256 => quote! { u256 },
-        TypeDefPrimitive::I256 => quote! { i256 },
+        TypeDefPrimitive::U256 => quote! { ink_wrapper_types::U256 },
+        TypeDefPrimitive::I256 => quote! { ink_wrapper_types::I256 },
         TypeDefPrimitive::Bool => quote! { bool },
         TypeDefPrimitive::Char => quote! { char },
         TypeDefPrimitive::Str => quote! { String },
@@ -610,14 +1498,16 @@ fn type_ref_tuple(
     tuple: &TypeDefTuple<PortableForm>,
     metadata: &InkProject,
     prefix: &str,
-) -> proc_macro2::TokenStream {
+    params: &TypeParams,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
     let typs = tuple
         .fields
         .iter()
-        .map(|t| type_ref_prefix(t.id, metadata, prefix));
-    quote! {
+        .map(|t| type_ref_params(t.id, metadata, prefix, params))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(quote! {
         (#(#typs),*)
-    }
+    })
 }
 
 /// Generates a type reference to an array type.
@@ -625,13 +1515,14 @@ fn type_ref_array(
     array: &TypeDefArray<PortableForm>,
     metadata: &InkProject,
     prefix: &str,
-) -> proc_macro2::TokenStream {
-    let typ = type_ref_prefix(array.type_param.id, metadata, prefix);
+    params: &TypeParams,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let typ = type_ref_params(array.type_param.id, metadata, prefix, params)?;
     // Cast to usize as otherwise we will get compilation errors on other archs.
     let len = array.len as usize;
-    quote! {
+    Ok(quote! {
         [ #typ ; #len ]
-    }
+    })
 }
 
 /// Generates a type reference to a sequence type.
@@ -639,11 +1530,26 @@ fn type_ref_sequence(
     sequence: &TypeDefSequence<PortableForm>,
     metadata: &InkProject,
     prefix: &str,
-) -> proc_macro2::TokenStream {
-    let typ = type_ref_prefix(sequence.type_param.id, metadata, prefix);
-    quote! {
+    params: &TypeParams,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let typ = type_ref_params(sequence.type_param.id, metadata, prefix, params)?;
+    Ok(quote! {
         Vec<#typ>
-    }
+    })
+}
+
+/// Generates a type reference to a bit sequence type.
+fn type_ref_bit_sequence(
+    bit_sequence: &TypeDefBitSequence<PortableForm>,
+    metadata: &InkProject,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let store = type_ref(bit_sequence.bit_store_type.id, metadata)?;
+    // The order type is `bitvec::order::Lsb0` or `bitvec::order::Msb0`.
+    let order = resolve(metadata, bit_sequence.bit_order_type.id)?;
+    let order = ident(order.path.segments.last().map_or("", String::as_str))?;
+    Ok(quote! {
+        ink_wrapper_types::bitvec::vec::BitVec<#store, ink_wrapper_types::bitvec::order::#order>
+    })
 }
 
 /// Generates a type reference to a compact type.
@@ -651,11 +1557,12 @@ fn type_ref_compact(
     compact: &TypeDefCompact<PortableForm>,
     metadata: &InkProject,
     prefix: &str,
-) -> proc_macro2::TokenStream {
-    let typ = type_ref_prefix(compact.type_param.id, metadata, prefix);
-    quote! {
+    params: &TypeParams,
+) -> Result<proc_macro2::TokenStream, GenerateError> {
+    let typ = type_ref_params(compact.type_param.id, metadata, prefix, params)?;
+    Ok(quote! {
         scale::Compact<#typ>
-    }
+    })
 }
 
 fn quote_docs(lines: &[String]) -> proc_macro2::TokenStream {
@@ -674,13 +1581,20 @@ fn quote_docs(lines: &[String]) -> proc_macro2::TokenStream {
 
 /// Resolves the type with the given ID.
 ///
-/// Panics if the type cannot be found in the metadata. We should only use types that are mentioned in the metadata
-/// file, so any type that cannot be found is a bug in the code generator or the metadata file.
-fn resolve(metadata: &InkProject, id: u32) -> &Type<PortableForm> {
+/// We should only use types that are mentioned in the metadata file, so any type that cannot be found means the
+/// metadata file is broken (or there is a bug in the code generator).
+fn resolve(metadata: &InkProject, id: u32) -> Result<&Type<PortableForm>, GenerateError> {
     metadata
         .registry()
         .resolve(id)
-        .unwrap_or_else(|| panic!("Type {} not found", id))
+        .ok_or(GenerateError::UnknownType(id))
+}
+
+/// Parses a name from the metadata into an identifier, failing for names that aren't valid Rust identifiers.
+///
+/// Keywords are rejected too, unless they are written as raw identifiers (`r#type`).
+pub(crate) fn ident(name: &str) -> Result<Ident, GenerateError> {
+    syn::parse_str::<Ident>(name).map_err(|_| GenerateError::InvalidIdentifier(name.to_string()))
 }
 
 /// Generates a name not already used by one of the arguments.
@@ -694,7 +1608,10 @@ fn new_name(name: &str, args: &[MessageParamSpec<PortableForm>]) -> Ident {
     format_ident!("{}", name)
 }
 
-/// Parses a hex string ("0x1234...") into a byte vector.
-fn hex_to_bytes(hex: &str) -> Vec<u8> {
-    hex::decode(hex.replace("0x", "")).unwrap()
+/// Parses a hex-encoded 32-byte hash ("0x1234...") into a byte vector.
+fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, GenerateError> {
+    hex::decode(hex.trim_start_matches("0x"))
+        .ok()
+        .filter(|bytes| bytes.len() == 32)
+        .ok_or_else(|| GenerateError::InvalidCodeHash(hex.to_string()))
 }
@@ -0,0 +1,273 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use quote::quote;
+use serde::Deserialize;
+
+use crate::{
+    codegen::{custom_types, generate_with_shared_types, SharedTypes},
+    format, format_wrapper, header,
+    metadata::ContractMetadata,
+    GenerateError, Options,
+};
+
+/// The configuration for generating the wrappers of several contracts in one run, usually read from
+/// `ink-wrapper.toml`:
+///
+/// ```toml
+/// # Optional, defines the types the contracts have in common once, in a module the wrappers import them from.
+/// [shared_types]
+/// output = "src/shared_types.rs"
+/// module = "crate::shared_types"
+///
+/// [[contract]]
+/// name = "my_contract"
+/// metadata = "contracts/my_contract/target/ink/my_contract.json"
+/// # Optional, relative to the output file like `--wasm-path`.
+/// wasm_path = "../contracts/my_contract/target/ink/my_contract.wasm"
+/// # Optional, see `--embed-wasm`.
+/// embed_wasm = false
+/// output = "src/my_contract.rs"
+/// # Optional, see `--lenient`.
+/// lenient = false
+/// # Optional, see `--flatten-results`.
+/// flatten_results = false
+/// ```
+///
+/// The `metadata` and `output` paths are relative to the directory of the config file.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(rename = "contract", default)]
+    pub contracts: Vec<ContractConfig>,
+    pub shared_types: Option<SharedTypesConfig>,
+}
+
+/// The configuration of the wrapper for a single contract.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ContractConfig {
+    /// The name of the contract, used in diagnostics.
+    pub name: String,
+    pub metadata: PathBuf,
+    pub wasm_path: Option<String>,
+    /// Embed the WASM from the `.contract` bundle given as `metadata`.
+    #[serde(default)]
+    pub embed_wasm: bool,
+    pub output: PathBuf,
+    #[serde(default)]
+    pub lenient: bool,
+    #[serde(default)]
+    pub flatten_results: bool,
+}
+
+/// The configuration of the module with the types shared by the contracts.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SharedTypesConfig {
+    /// The file to write the module to.
+    pub output: PathBuf,
+    /// The path the wrappers import the shared types from, e.g. `crate::shared_types`.
+    pub module: String,
+}
+
+/// A file generated from a [Config].
+#[derive(Debug, Clone)]
+pub struct GeneratedFile {
+    pub path: PathBuf,
+    pub contents: String,
+    /// The items skipped in lenient mode.
+    pub skipped: Vec<GenerateError>,
+}
+
+impl Config {
+    /// Reads the config file at the given path, resolving the paths in it against the directory of the file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        let config = Self::from_toml(&contents)
+            .with_context(|| format!("Failed to parse config file {}", path.display()))?;
+
+        let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+        Ok(config.relative_to(base_dir))
+    }
+
+    /// Parses the contents of a config file. The paths are left as they are.
+    pub fn from_toml(contents: &str) -> Result<Self> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    /// Resolves the relative paths in the config against `base_dir`.
+    pub fn relative_to(mut self, base_dir: &Path) -> Self {
+        for contract in &mut self.contracts {
+            contract.metadata = base_dir.join(&contract.metadata);
+            contract.output = base_dir.join(&contract.output);
+        }
+        if let Some(shared_types) = &mut self.shared_types {
+            shared_types.output = base_dir.join(&shared_types.output);
+        }
+        self
+    }
+
+    /// Generates the wrappers of all the contracts and, if configured, the module with their shared types.
+    ///
+    /// A type is shared if at least two contracts define it, and every contract that defines a type with its name defines
+    /// it the same way. Nothing is written to disk.
+    pub fn generate(&self) -> Result<Vec<GeneratedFile>> {
+        let contracts = self
+            .contracts
+            .iter()
+            .map(|contract| Ok((contract, contract.read_metadata()?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let shared = match &self.shared_types {
+            Some(shared_types) => Some(SharedTypes {
+                module: syn::parse_str(&shared_types.module).with_context(|| {
+                    format!("Invalid shared types module `{}`", shared_types.module)
+                })?,
+                definitions: shared_definitions(&contracts)?,
+            }),
+            None => None,
+        };
+        let empty = SharedTypes {
+            module: syn::parse_quote!(self),
+            definitions: BTreeMap::new(),
+        };
+
+        let mut files = contracts
+            .iter()
+            .map(|(config, contract)| {
+                let (tokens, skipped) = generate_with_shared_types(
+                    contract,
+                    config.wasm_path.clone(),
+                    shared.as_ref().unwrap_or(&empty),
+                    &config.options(),
+                )
+                .with_context(|| format!("Failed to generate the wrapper for {}", config.name))?;
+
+                Ok(GeneratedFile {
+                    path: config.output.clone(),
+                    contents: format_wrapper(contract, tokens)?,
+                    skipped,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if let (Some(shared_types), Some(shared)) = (&self.shared_types, shared) {
+            let names = self
+                .contracts
+                .iter()
+                .map(|contract| contract.name.as_str())
+                .collect::<Vec<_>>();
+            let definitions = shared.definitions.values();
+
+            files.push(GeneratedFile {
+                path: shared_types.output.clone(),
+                contents: format!(
+                    "{}\n{}",
+                    header(&format!("Types shared by: {}", names.join(", "))),
+                    format(quote! { #(#definitions)* })?
+                ),
+                skipped: Vec::new(),
+            });
+        }
+
+        Ok(files)
+    }
+}
+
+impl ContractConfig {
+    fn options(&self) -> Options {
+        Options {
+            lenient: self.lenient,
+            flatten_results: self.flatten_results,
+        }
+    }
+
+    /// Reads the metadata of the contract, checking the WASM given by `wasm_path` against it.
+    fn read_metadata(&self) -> Result<ContractMetadata> {
+        let mut metadata = ContractMetadata::from_file(&self.metadata)?;
+        if let Some(wasm_path) = &self.wasm_path {
+            let base_dir = self.output.parent().unwrap_or_else(|| Path::new(""));
+            metadata.verify_wasm(base_dir.join(wasm_path))?;
+        }
+        if !self.embed_wasm {
+            metadata.wasm = None;
+        } else if metadata.wasm.is_none() {
+            bail!(
+                "{} is not a .contract bundle with the WASM",
+                self.metadata.display()
+            );
+        }
+        Ok(metadata)
+    }
+}
+
+/// Finds the custom types that can be defined once for all the contracts.
+fn shared_definitions(
+    contracts: &[(&ContractConfig, ContractMetadata)],
+) -> Result<BTreeMap<String, proc_macro2::TokenStream>> {
+    let mut definitions = BTreeMap::<String, Vec<proc_macro2::TokenStream>>::new();
+
+    for (config, contract) in contracts {
+        let types = custom_types(contract, config.lenient)
+            .with_context(|| format!("Failed to generate the wrapper for {}", config.name))?;
+        for (name, definition) in types {
+            definitions.entry(name).or_default().push(definition);
+        }
+    }
+
+    let mut shared = definitions
+        .iter()
+        .filter(|(_, definitions)| {
+            definitions.len() > 1
+                && definitions
+                    .iter()
+                    .all(|definition| definition.to_string() == definitions[0].to_string())
+        })
+        .map(|(name, definitions)| (name.clone(), definitions[0].clone()))
+        .collect::<BTreeMap<_, _>>();
+
+    // A type defined the same way by all the contracts can still reference types that differ between them, so it's
+    // only shared if all the custom types it references (directly or through other types) are shared as well.
+    loop {
+        let unshared = shared
+            .iter()
+            .filter(|(name, definition)| {
+                let mut referenced = HashSet::new();
+                idents(definition, &mut referenced);
+                referenced.iter().any(|referenced| {
+                    referenced != *name
+                        && definitions.contains_key(referenced)
+                        && !shared.contains_key(referenced)
+                })
+            })
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+
+        if unshared.is_empty() {
+            return Ok(shared);
+        }
+        for name in unshared {
+            shared.remove(&name);
+        }
+    }
+}
+
+/// Collects all the identifiers in a token stream.
+fn idents(tokens: &proc_macro2::TokenStream, idents_found: &mut HashSet<String>) {
+    for token in tokens.clone() {
+        match token {
+            proc_macro2::TokenTree::Ident(ident) => {
+                idents_found.insert(ident.to_string());
+            }
+            proc_macro2::TokenTree::Group(group) => idents(&group.stream(), idents_found),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use std::fmt;
+
+/// An error that prevents generating the wrapper or, in lenient mode, one of its items.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum GenerateError {
+    #[error("type {0} not found in the metadata")]
+    UnknownType(u32),
+    #[error("mix of named and unnamed fields")]
+    MixedFields,
+    #[error("`{0}` is not a valid identifier")]
+    InvalidIdentifier(String),
+    #[error("missing return type")]
+    MissingReturnType,
+    #[error("more than 256 events")]
+    TooManyEvents,
+    #[error("invalid code hash `{0}`, expected 32 hex-encoded bytes")]
+    InvalidCodeHash(String),
+    /// An error in generating a particular item of the wrapper.
+    #[error("{item}: {error}")]
+    Item {
+        item: Item,
+        error: Box<GenerateError>,
+    },
+}
+
+/// An item of the wrapper generated for a part of the metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Item {
+    /// The definition of the custom type with the given ID and path.
+    Type {
+        id: u32,
+        path: String,
+    },
+    Constructor(String),
+    Message(String),
+    Event(String),
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Item::Type { id, path } => write!(f, "type {} (`{}`)", id, path),
+            Item::Constructor(label) => write!(f, "constructor `{}`", label),
+            Item::Message(label) => write!(f, "message `{}`", label),
+            Item::Event(label) => write!(f, "event `{}`", label),
+        }
+    }
+}
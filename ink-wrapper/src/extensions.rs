@@ -1,6 +1,8 @@
 use ink_metadata::MessageSpec;
 use scale_info::{form::PortableForm, Field, Type, TypeDef, TypeDefComposite, Variant};
 
+use crate::{codegen::ident, GenerateError};
+
 pub trait TypeExtensions {
     /// Returns true if the type is a rust primitive.
     fn is_primitive(&self) -> bool;
@@ -15,6 +17,10 @@ pub trait TypeExtensions {
     /// Returns true if the type is a builtin type.
     fn is_builtin(&self) -> bool;
 
+    /// Returns true if the type is defined in the bitvec crate, like the order types of bit sequences. These are
+    /// referenced through the `bitvec` reexport in ink_wrapper_types.
+    fn is_bitvec(&self) -> bool;
+
     /// Returns true if the type is defined in the contract itself.
     fn is_custom(&self) -> bool;
 
@@ -26,7 +32,7 @@ pub trait TypeExtensions {
     ///
     /// It's the full path to the type for ink! types and just the name for other types. That's because any custom types
     /// for the contract will be defined in the same module as the functions that use them.
-    fn qualified_name(&self) -> proc_macro2::TokenStream;
+    fn qualified_name(&self) -> Result<proc_macro2::TokenStream, GenerateError>;
 }
 
 impl TypeExtensions for Type<PortableForm> {
@@ -51,43 +57,44 @@ impl TypeExtensions for Type<PortableForm> {
         self.path.segments.len() == 1
     }
 
+    fn is_bitvec(&self) -> bool {
+        !self.path.segments.is_empty() && self.path.segments[0] == "bitvec"
+    }
+
     fn is_lang_error(&self) -> bool {
         self.is_ink() && self.path.segments.last().unwrap() == "LangError"
     }
 
     fn is_custom(&self) -> bool {
-        !self.is_primitive() && !self.is_ink() && !self.is_builtin()
+        !self.is_primitive() && !self.is_ink() && !self.is_builtin() && !self.is_bitvec()
     }
 
-    fn qualified_name(&self) -> proc_macro2::TokenStream {
+    fn qualified_name(&self) -> Result<proc_macro2::TokenStream, GenerateError> {
         if self.is_lang_error() {
-            quote::quote! { ink_wrapper_types::InkLangError }
-        } else if self.is_ink_types() {
-            let last_segment = quote::format_ident!("{}", self.path.segments.last().unwrap());
-            quote::quote! { ink_primitives::#last_segment }
+            return Ok(quote::quote! { ink_wrapper_types::InkLangError });
+        }
+
+        let last_segment = ident(self.path.segments.last().map_or("", String::as_str))?;
+        if self.is_ink_types() {
+            Ok(quote::quote! { ink_primitives::#last_segment })
         } else {
-            let last_segment = quote::format_ident!("{}", self.path.segments.last().unwrap());
-            quote::quote! { #last_segment }
+            Ok(quote::quote! { #last_segment })
         }
     }
 }
 
 pub trait MessageSpecExtensions {
+    /// Returns the path of the trait the message belongs to, e.g. `PSP22` for `PSP22::transfer` or
+    /// `psp22::extensions::metadata::PSP22Metadata` for `psp22::extensions::metadata::PSP22Metadata::token_name`.
     fn trait_name(&self) -> Option<String>;
     fn method_name(&self) -> String;
 }
 
 impl MessageSpecExtensions for MessageSpec<PortableForm> {
     fn trait_name(&self) -> Option<String> {
-        let parts = self.label().split("::").collect::<Vec<&str>>();
-        match parts.len() {
-            1 => None,
-            2 => Some(parts[0].to_string()),
-            _ => panic!(
-                "Nested modules in method names are unsupported yet: {}",
-                self.label()
-            ),
-        }
+        self.label()
+            .rsplit_once("::")
+            .map(|(trait_name, _)| trait_name.to_string())
     }
 
     fn method_name(&self) -> String {
@@ -101,55 +108,70 @@ impl MessageSpecExtensions for MessageSpec<PortableForm> {
 /// first.
 pub enum Fields {
     /// A type with named fields.
-    Named(Vec<(String, u32)>),
+    Named(Vec<(String, FieldType)>),
     /// A type with unnamed fields.
-    Unnamed(Vec<u32>),
+    Unnamed(Vec<FieldType>),
 }
 
-impl From<Vec<&Field<PortableForm>>> for Fields {
-    fn from(fields: Vec<&Field<PortableForm>>) -> Self {
+/// The type of a field of a struct or enum.
+pub struct FieldType {
+    /// The ID of the type in the registry.
+    pub id: u32,
+    /// The name of the type as written in the contract's source, e.g. `Vec<T>` for a field of a generic type.
+    pub type_name: Option<String>,
+}
+
+impl From<&Field<PortableForm>> for FieldType {
+    fn from(field: &Field<PortableForm>) -> Self {
+        Self {
+            id: field.ty.id,
+            type_name: field.type_name.clone(),
+        }
+    }
+}
+
+impl TryFrom<Vec<&Field<PortableForm>>> for Fields {
+    type Error = GenerateError;
+
+    fn try_from(fields: Vec<&Field<PortableForm>>) -> Result<Self, GenerateError> {
         if fields.iter().all(|f| f.name.is_none()) {
-            Fields::Unnamed(fields.iter().map(|f| f.ty.id).collect())
+            Ok(Fields::Unnamed(
+                fields.iter().map(|f| (*f).into()).collect(),
+            ))
         } else {
-            Fields::Named(
+            Ok(Fields::Named(
                 fields
                     .iter()
                     .map(|f| {
-                        (
-                            f.name
-                                .as_ref()
-                                .unwrap_or_else(|| {
-                                    panic!("{:?} has a mix of named and unnamed fields", fields)
-                                })
-                                .to_string(),
-                            f.ty.id,
-                        )
+                        let name = f.name.as_ref().ok_or(GenerateError::MixedFields)?;
+                        Ok((name.to_string(), (*f).into()))
                     })
-                    .collect(),
-            )
+                    .collect::<Result<_, _>>()?,
+            ))
         }
     }
 }
 
 /// An extension trait that allows extraction of a [Fields] from the implementor.
 pub trait AggregateFields {
-    fn aggregate_fields(&self) -> Fields;
+    /// Fails if the fields are a mix of named and unnamed ones.
+    fn aggregate_fields(&self) -> Result<Fields, GenerateError>;
 }
 
 impl AggregateFields for Variant<PortableForm> {
-    fn aggregate_fields(&self) -> Fields {
+    fn aggregate_fields(&self) -> Result<Fields, GenerateError> {
         self.fields
             .iter()
             .collect::<Vec<&Field<PortableForm>>>()
-            .into()
+            .try_into()
     }
 }
 
 impl AggregateFields for TypeDefComposite<PortableForm> {
-    fn aggregate_fields(&self) -> Fields {
+    fn aggregate_fields(&self) -> Result<Fields, GenerateError> {
         self.fields
             .iter()
             .collect::<Vec<&Field<PortableForm>>>()
-            .into()
+            .try_into()
     }
 }
@@ -0,0 +1,44 @@
+//! Generates type-safe code for calling an ink! smart contract based on the metadata file for that contract.
+//!
+//! Besides the `ink-wrapper` binary, the generator can be used as a library, most commonly from a `build.rs` script
+//! through [Builder]. To generate a wrapper at compile time instead, use `ink_wrapper_macro::contract!` from the
+//! `ink-wrapper-macro` crate. It can't be re-exported from here, as the macro itself depends on this crate.
+mod builder;
+mod check;
+mod codegen;
+pub mod config;
+mod error;
+mod extensions;
+pub mod metadata;
+
+use anyhow::Result;
+pub use builder::Builder;
+pub use check::diff_wrappers;
+pub use codegen::{generate, generate_lenient, generate_with_options, Options};
+pub use error::{GenerateError, Item};
+use metadata::ContractMetadata;
+
+/// Formats the generated tokens as a Rust source file.
+pub fn format(tokens: proc_macro2::TokenStream) -> Result<String> {
+    let file = syn::parse2::<syn::File>(tokens)?;
+    Ok(prettyplease::unparse(&file))
+}
+
+/// Formats the wrapper generated for the contract as a Rust source file, starting with a header that names the version
+/// of ink-wrapper and the hash of the metadata file it was generated with.
+pub fn format_wrapper(
+    contract: &ContractMetadata,
+    tokens: proc_macro2::TokenStream,
+) -> Result<String> {
+    let metadata_hash = format!("Metadata hash: 0x{}", hex::encode(contract.metadata_hash));
+    Ok(format!("{}\n{}", header(&metadata_hash), format(tokens)?))
+}
+
+/// Returns the header comment of a generated file, with the version of ink-wrapper and the given details.
+fn header(details: &str) -> String {
+    format!(
+        "// This file was auto-generated with ink-wrapper {} (https://crates.io/crates/ink-wrapper).\n// {}\n",
+        env!("CARGO_PKG_VERSION"),
+        details
+    )
+}
@@ -1,23 +1,29 @@
-mod codegen;
-mod extensions;
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
 
-use std::{fs, io::Write};
-
-use anyhow::Result;
-use clap::Parser;
-use codegen::generate;
-use ink_metadata::InkProject;
-use serde::{Deserialize, Serialize};
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use ink_wrapper::{
+    config::Config, diff_wrappers, format_wrapper, generate_with_options,
+    metadata::ContractMetadata, Options,
+};
 
 #[derive(Parser)]
-#[command(version, about)]
+#[command(version, about, subcommand_negates_reqs = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(
         short,
         long,
+        required = true,
         help = "Path to the metadata file to generate a wrapper for."
     )]
-    metadata: String,
+    metadata: Option<String>,
 
     #[arg(
         long,
@@ -25,31 +31,171 @@ struct Args {
             in the output file. Making it possible to upload the contract to a chain."
     )]
     wasm_path: Option<String>,
-}
 
-/// Struct for deserializing metadata.json that contains the fields not present in an InkProject.
-#[derive(Debug, Serialize, Deserialize)]
-struct Metadata {
-    source: Source,
+    #[arg(
+        long,
+        conflicts_with = "wasm_path",
+        help = "Embed the WASM from the `.contract` bundle given as the metadata in the output file, making it possible \
+            to upload the contract to a chain."
+    )]
+    embed_wasm: bool,
+
+    #[arg(
+        long,
+        help = "Skip the items that can't be generated with a warning instead of failing. Types are replaced with stubs \
+            that fail to decode, messages, constructors and events are left out."
+    )]
+    lenient: bool,
+
+    #[arg(
+        long,
+        help = "Make messages return `ink_wrapper_types::Flat<T>` or `ink_wrapper_types::FlatResult<T, E>`, which can \
+            be flattened into a `Result<T, CallError<E>>`, instead of nested `Result`s with `InkLangError`."
+    )]
+    flatten_results: bool,
+
+    #[arg(
+        short,
+        long,
+        help = "File to write the formatted wrapper to. If not provided, the wrapper is written to the standard output."
+    )]
+    output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        requires = "output",
+        help = "Instead of writing the output file, check that it's up to date with the metadata. Prints a diff and \
+            fails if it's not. Differences in formatting and comments are ignored."
+    )]
+    check: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Source {
-    hash: String,
+#[derive(Subcommand)]
+enum Command {
+    /// Generate the wrappers of all the contracts listed in a config file.
+    Generate {
+        #[arg(
+            short,
+            long,
+            help = "Path to the config file (usually ink-wrapper.toml) listing the contracts."
+        )]
+        config: PathBuf,
+
+        #[arg(
+            long,
+            help = "Instead of writing the output files, check that they are up to date with the metadata."
+        )]
+        check: bool,
+    },
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
-    let jsonized = fs::read_to_string(args.metadata)?;
-    let metadata: Metadata = serde_json::from_str(&jsonized)?;
-    let code_hash = metadata.source.hash;
-    let metadata: InkProject = serde_json::from_str(&jsonized)?;
 
-    let tokens: proc_macro2::TokenStream = generate(&metadata, code_hash, args.wasm_path);
+    match args.command {
+        Some(Command::Generate { config, check }) => generate_from_config(&config, check),
+        None => generate_single(args),
+    }
+}
+
+fn generate_single(args: Args) -> Result<()> {
+    let metadata_path = args
+        .metadata
+        .expect("--metadata is required without a subcommand");
+    let mut metadata = ContractMetadata::from_file(&metadata_path)?;
+    if !args.embed_wasm {
+        metadata.wasm = None;
+    } else if metadata.wasm.is_none() {
+        bail!("{} is not a .contract bundle with the WASM", metadata_path);
+    }
+
+    if let Some(wasm_path) = &args.wasm_path {
+        verify_wasm(&metadata, wasm_path, args.output.as_deref())?;
+    }
 
-    let stdout = std::io::stdout();
+    let options = Options {
+        lenient: args.lenient,
+        flatten_results: args.flatten_results,
+    };
+    let (tokens, skipped) = generate_with_options(&metadata, args.wasm_path, &options)?;
+    for error in skipped {
+        eprintln!("warning: skipped {}", error);
+    }
 
-    stdout.lock().write_all(tokens.to_string().as_bytes())?;
+    let wrapper = format_wrapper(&metadata, tokens)?;
+
+    match args.output {
+        Some(output) if args.check => {
+            if !is_up_to_date(&output, &wrapper)? {
+                bail!("{} is out of date", output.display());
+            }
+        }
+        Some(output) => write(&output, &wrapper)?,
+        None => std::io::stdout().lock().write_all(wrapper.as_bytes())?,
+    }
 
     Ok(())
 }
+
+fn generate_from_config(config_path: &Path, check: bool) -> Result<()> {
+    let config = Config::from_file(config_path)?;
+    let mut out_of_date = Vec::new();
+
+    for file in config.generate()? {
+        for error in &file.skipped {
+            eprintln!("warning: {}: skipped {}", file.path.display(), error);
+        }
+
+        if !check {
+            write(&file.path, &file.contents)?;
+        } else if !is_up_to_date(&file.path, &file.contents)? {
+            out_of_date.push(file.path.display().to_string());
+        }
+    }
+
+    if !out_of_date.is_empty() {
+        bail!("Out of date: {}", out_of_date.join(", "));
+    }
+
+    Ok(())
+}
+
+/// Checks the WASM at `wasm_path`, which is relative to the output file, against the code hash in the metadata.
+///
+/// The wrapper may be written to the standard output, in which case the path is tried relative to the current
+/// directory and only a warning is printed if there's no WASM there.
+fn verify_wasm(metadata: &ContractMetadata, wasm_path: &str, output: Option<&Path>) -> Result<()> {
+    match output {
+        Some(output) => {
+            let base_dir = output.parent().unwrap_or_else(|| Path::new(""));
+            metadata.verify_wasm(base_dir.join(wasm_path))
+        }
+        None if Path::new(wasm_path).exists() => metadata.verify_wasm(wasm_path),
+        None => {
+            eprintln!(
+                "warning: couldn't verify the hash of {}, it's not relative to the current directory",
+                wasm_path
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Compares the file with the freshly generated wrapper, printing a diff if they differ.
+fn is_up_to_date(path: &Path, wrapper: &str) -> Result<bool> {
+    let existing =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    match diff_wrappers(&existing, wrapper) {
+        Some(diff) => {
+            eprintln!("{}:", path.display());
+            eprint!("{}", diff);
+            Ok(false)
+        }
+        None => Ok(true),
+    }
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    fs::write(path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
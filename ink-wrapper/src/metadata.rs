@@ -0,0 +1,206 @@
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, bail, Context, Result};
+use blake2::{digest::consts::U32, Blake2b, Digest as _};
+use ink_metadata::{layout::Layout, InkProject};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Struct for deserializing metadata.json that contains the fields not present in an InkProject.
+#[derive(Debug, Serialize, Deserialize)]
+struct Metadata {
+    source: Source,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Source {
+    hash: String,
+    /// The hex-encoded WASM of the contract, only present in `.contract` bundles.
+    wasm: Option<String>,
+}
+
+/// The version of the metadata format, which follows the major version of ink! the contract was built with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataVersion {
+    V4,
+    V5,
+}
+
+/// The parts of a contract's metadata file needed to generate a wrapper.
+pub struct ContractMetadata {
+    /// The ink! project described by the metadata.
+    pub project: InkProject,
+    /// The hex-encoded hash of the contract's code (`source.hash` in the metadata file).
+    pub code_hash: String,
+    /// The version of the metadata format.
+    pub version: MetadataVersion,
+    /// The signature topics of the events, in the order of `project.spec().events()`. `None` for anonymous events,
+    /// which can only be told apart in version 5 metadata.
+    pub event_signature_topics: Vec<Option<[u8; 32]>>,
+    /// The BLAKE2b-256 hash of the metadata file the wrapper is generated from.
+    pub metadata_hash: [u8; 32],
+    /// The WASM of the contract if the metadata comes from a `.contract` bundle, checked against the code hash. It's
+    /// embedded in the wrapper unless a WASM path is given.
+    pub wasm: Option<Vec<u8>>,
+}
+
+impl ContractMetadata {
+    /// Reads and parses the metadata file at the given path.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let jsonized = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read metadata file {}", path.display()))?;
+        Self::from_json(&jsonized)
+            .with_context(|| format!("Failed to parse metadata file {}", path.display()))
+    }
+
+    /// Parses the contents of a metadata file or a `.contract` bundle, in either version 4 or 5 of the format.
+    pub fn from_json(jsonized: &str) -> Result<Self> {
+        let metadata_hash = Blake2b::<U32>::digest(jsonized.as_bytes()).into();
+        let mut json: Value = serde_json::from_str(jsonized)?;
+        let version = match &json["version"] {
+            Value::String(version) if version == "4" => MetadataVersion::V4,
+            Value::Number(version) if version.as_u64() == Some(5) => MetadataVersion::V5,
+            Value::String(version) if version == "5" => MetadataVersion::V5,
+            version => bail!("Unsupported metadata version: {}", version),
+        };
+
+        let v5_signature_topics = match version {
+            MetadataVersion::V4 => None,
+            MetadataVersion::V5 => Some(v5_signature_topics(&json)?),
+        };
+
+        // Apart from the event details read above, version 5 only adds fields that `InkProject` (from ink! 4) ignores,
+        // so it's enough to pretend the metadata is in version 4.
+        json["version"] = Value::String("4".to_string());
+
+        let metadata: Metadata = serde_json::from_value(json.clone())?;
+        let project: InkProject = serde_json::from_value(json)?;
+
+        let event_signature_topics = match v5_signature_topics {
+            Some(topics) => topics,
+            None => v4_signature_topics(&project)?,
+        };
+
+        let wasm = match &metadata.source.wasm {
+            Some(wasm) => Some(bundled_wasm(wasm, &metadata.source.hash)?),
+            None => None,
+        };
+
+        Ok(Self {
+            project,
+            code_hash: metadata.source.hash,
+            version,
+            event_signature_topics,
+            metadata_hash,
+            wasm,
+        })
+    }
+
+    /// Checks that the WASM file at the given path is the code described by the metadata, i.e. that its hash matches
+    /// `code_hash`. Otherwise the WASM or the metadata are out of date, and uploading the code would fail.
+    pub fn verify_wasm(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let wasm = fs::read(path)
+            .with_context(|| format!("Failed to read WASM file {}", path.display()))?;
+        check_code_hash(&wasm, &self.code_hash, &path.display().to_string())
+            .context("The WASM and the metadata are from different builds")
+    }
+}
+
+/// Returns the hash of the contract's code, as in `source.hash` of the metadata.
+pub fn code_hash(wasm: &[u8]) -> [u8; 32] {
+    Blake2b::<U32>::digest(wasm).into()
+}
+
+/// Decodes the WASM from a `.contract` bundle, checking that it matches the code hash.
+fn bundled_wasm(wasm: &str, expected_hash: &str) -> Result<Vec<u8>> {
+    let wasm = hex::decode(wasm.trim_start_matches("0x")).context("Invalid source.wasm")?;
+    check_code_hash(&wasm, expected_hash, "the WASM in the bundle")?;
+    Ok(wasm)
+}
+
+/// Fails if the hash of `wasm` (described as `what` in the error) isn't `expected_hash`.
+fn check_code_hash(wasm: &[u8], expected_hash: &str, what: &str) -> Result<()> {
+    let actual_hash = format!("0x{}", hex::encode(code_hash(wasm)));
+
+    if !actual_hash.eq_ignore_ascii_case(expected_hash) {
+        bail!(
+            "The hash of {} ({}) doesn't match source.hash ({})",
+            what,
+            actual_hash,
+            expected_hash
+        );
+    }
+
+    Ok(())
+}
+
+/// Reads the `signature_topic` of each event in version 5 metadata.
+fn v5_signature_topics(json: &Value) -> Result<Vec<Option<[u8; 32]>>> {
+    let events = json["spec"]["events"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Missing spec.events"))?;
+
+    events
+        .iter()
+        .map(|event| match &event["signature_topic"] {
+            Value::Null => Ok(None),
+            Value::String(topic) => {
+                let bytes = hex::decode(topic.trim_start_matches("0x"))?;
+                let topic = bytes
+                    .try_into()
+                    .map_err(|_| anyhow!("Signature topic {} is not 32 bytes long", topic))?;
+                Ok(Some(topic))
+            }
+            topic => bail!("Invalid signature topic: {}", topic),
+        })
+        .collect()
+}
+
+/// Computes the signature topics ink! 4 emits with events. Version 4 metadata doesn't say which events are anonymous,
+/// so all of them get a topic.
+fn v4_signature_topics(project: &InkProject) -> Result<Vec<Option<[u8; 32]>>> {
+    let storage_name = storage_name(project)?;
+
+    Ok(project
+        .spec()
+        .events()
+        .iter()
+        .map(|event| {
+            Some(v4_signature_topic(&format!(
+                "{}::{}",
+                storage_name,
+                event.label()
+            )))
+        })
+        .collect())
+}
+
+/// Returns the name of the contract's storage struct, which ink! 4 uses in event signatures.
+fn storage_name(project: &InkProject) -> Result<&str> {
+    match project.layout() {
+        Layout::Root(root) => match root.layout() {
+            Layout::Struct(storage) => Ok(storage.name()),
+            _ => bail!("The root storage layout is expected to be a struct"),
+        },
+        _ => bail!("The storage layout is expected to start with a root layout"),
+    }
+}
+
+/// Computes the topic ink! 4 emits for the given signature.
+///
+/// ink! encodes the signature with an empty prefix, which SCALE-encodes as a single zero byte (the length), and uses
+/// the result as the topic directly if it fits in 32 bytes (padded with zeroes), or its BLAKE2b-256 hash otherwise.
+fn v4_signature_topic(signature: &str) -> [u8; 32] {
+    let mut topic = [0u8; 32];
+    let encoded = [&[0u8][..], signature.as_bytes()].concat();
+
+    if encoded.len() <= topic.len() {
+        topic[..encoded.len()].copy_from_slice(&encoded);
+    } else {
+        topic.copy_from_slice(&Blake2b::<U32>::digest(&encoded));
+    }
+
+    topic
+}
@@ -0,0 +1,552 @@
+use ink_wrapper::{
+    config::{Config, GeneratedFile},
+    diff_wrappers, format, format_wrapper, generate, generate_lenient, generate_with_options,
+    metadata::{code_hash, ContractMetadata, MetadataVersion},
+    Builder, GenerateError, Options,
+};
+
+const V4_FIXTURE: &str = "tests/fixtures/test_contract_v4.json";
+const V5_FIXTURE: &str = "tests/fixtures/test_contract_v5.json";
+
+fn generate_fixture(path: &str) -> (ContractMetadata, String) {
+    let metadata = ContractMetadata::from_file(path).unwrap();
+    let code = format(generate(&metadata, None).unwrap()).unwrap();
+    (metadata, code)
+}
+
+fn signature_topic(metadata: &ContractMetadata, label: &str) -> Option<[u8; 32]> {
+    let index = metadata
+        .project
+        .spec()
+        .events()
+        .iter()
+        .position(|event| event.label() == label)
+        .unwrap();
+    metadata.event_signature_topics[index]
+}
+
+#[test]
+fn test_v4_metadata() {
+    let (metadata, code) = generate_fixture(V4_FIXTURE);
+
+    assert_eq!(metadata.version, MetadataVersion::V4);
+    assert_eq!(metadata.event_signature_topics.len(), 4);
+
+    let mut expected = [0u8; 32];
+    expected[1..21].copy_from_slice(b"TestContract::Event1");
+    assert_eq!(signature_topic(&metadata, "Event1"), Some(expected));
+
+    assert!(code.contains("pub const EVENT1_SIGNATURE_TOPIC"));
+    assert!(code.contains("<Self as scale::Decode>::decode"));
+}
+
+#[test]
+fn test_v5_metadata() {
+    let (metadata, code) = generate_fixture(V5_FIXTURE);
+
+    assert_eq!(metadata.version, MetadataVersion::V5);
+    assert_eq!(metadata.event_signature_topics.len(), 4);
+
+    let expected =
+        hex::decode("893ce90a85f90a57a58c43bc4d4ceb446a761692118f6791a8adaf84e2aadb85").unwrap();
+    assert_eq!(
+        signature_topic(&metadata, "Event1").map(Vec::from),
+        Some(expected)
+    );
+
+    assert!(code.contains("pub const EVENT1_SIGNATURE_TOPIC"));
+    assert!(code.contains("scale::DecodeAll"));
+}
+
+#[test]
+fn test_v5_anonymous_events() {
+    let jsonized = std::fs::read_to_string(V5_FIXTURE).unwrap().replace(
+        "\"0x893ce90a85f90a57a58c43bc4d4ceb446a761692118f6791a8adaf84e2aadb85\"",
+        "null",
+    );
+    let metadata = ContractMetadata::from_json(&jsonized).unwrap();
+    let code = format(generate(&metadata, None).unwrap()).unwrap();
+
+    assert_eq!(signature_topic(&metadata, "Event1"), None);
+    assert!(!code.contains("EVENT1_SIGNATURE_TOPIC"));
+    assert!(code.contains("EVENT2_SIGNATURE_TOPIC"));
+}
+
+#[test]
+fn test_unsupported_version() {
+    let jsonized = std::fs::read_to_string(V4_FIXTURE).unwrap().replacen(
+        "\"version\": \"4\"",
+        "\"version\": \"3\"",
+        1,
+    );
+
+    assert!(ContractMetadata::from_json(&jsonized).is_err());
+}
+
+#[test]
+fn test_nested_trait_namespaces() {
+    let (_, code) = generate_fixture("tests/fixtures/psp22_nested_traits.json");
+    let file = syn::parse_file(&code).unwrap();
+
+    let module = |items: &[syn::Item], name: &str| -> Vec<syn::Item> {
+        items
+            .iter()
+            .find_map(|item| match item {
+                syn::Item::Mod(module) if module.ident == name => {
+                    Some(module.content.clone().unwrap().1)
+                }
+                _ => None,
+            })
+            .unwrap()
+    };
+    let has_trait = |items: &[syn::Item], name: &str| {
+        items
+            .iter()
+            .any(|item| matches!(item, syn::Item::Trait(t) if t.ident == name))
+    };
+
+    let psp22 = module(&file.items, "psp22");
+    assert!(has_trait(&psp22, "PSP22"));
+    let burnable = module(&module(&psp22, "extensions"), "burnable");
+    assert!(has_trait(&burnable, "PSP22Burnable"));
+}
+
+/// The names of the traits among `items`, in order.
+fn trait_names(items: &[syn::Item]) -> Vec<String> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Trait(item) => Some(item.ident.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_sibling_traits_are_ordered() {
+    let metadata = ContractMetadata::from_file("tests/fixtures/psp22_sibling_traits.json").unwrap();
+    let code = format(generate(&metadata, None).unwrap()).unwrap();
+    assert!(code == format(generate(&metadata, None).unwrap()).unwrap());
+
+    let psp22 = syn::parse_file(&code)
+        .unwrap()
+        .items
+        .into_iter()
+        .find_map(|item| match item {
+            syn::Item::Mod(module) if module.ident == "psp22" => Some(module.content.unwrap().1),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(trait_names(&psp22), ["PSP22", "PSP22Burnable"]);
+}
+
+#[test]
+fn test_top_level_traits_are_ordered() {
+    let json = std::fs::read_to_string("tests/fixtures/psp22_nested_traits.json")
+        .unwrap()
+        .replace("psp22::extensions::burnable::", "")
+        .replace("psp22::", "");
+    let metadata = ContractMetadata::from_json(&json).unwrap();
+    let code = format(generate(&metadata, None).unwrap()).unwrap();
+    assert!(code == format(generate(&metadata, None).unwrap()).unwrap());
+
+    let items = syn::parse_file(&code).unwrap().items;
+    assert_eq!(trait_names(&items), ["PSP22", "PSP22Burnable"]);
+}
+
+#[test]
+fn test_mangled_names_are_unique() {
+    // `Enum1` is moved into a `nested` module and renamed to `Struct1`, and `Struct2` is renamed to the name
+    // `nested::Struct1` would be mangled into.
+    let mut json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(V4_FIXTURE).unwrap()).unwrap();
+    json["types"][15]["type"]["path"] =
+        serde_json::json!(["test_contract", "test_contract", "nested", "Struct1"]);
+    json["types"][17]["type"]["path"] =
+        serde_json::json!(["test_contract", "test_contract", "NestedStruct1"]);
+    let names = type_names(&ContractMetadata::from_json(&json.to_string()).unwrap());
+
+    assert!(names.contains(&"Struct1".to_string()));
+    assert!(names.contains(&"TestContractNestedStruct1".to_string()));
+    assert!(names.contains(&"NestedStruct1".to_string()));
+    let unique = names.iter().collect::<std::collections::HashSet<_>>();
+    assert_eq!(unique.len(), names.len());
+}
+
+#[test]
+fn test_mangled_names_of_different_paths_are_unique() {
+    // `b_c::Error` and `b::c::Error` would both be mangled into `BCError`.
+    let mut json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(V4_FIXTURE).unwrap()).unwrap();
+    json["types"][15]["type"]["path"] = serde_json::json!(["p", "b_c", "Error"]);
+    json["types"][17]["type"]["path"] = serde_json::json!(["p", "b", "c", "Error"]);
+    let names = type_names(&ContractMetadata::from_json(&json.to_string()).unwrap());
+
+    // Prepending all the modules doesn't help either, so the index of the type in the registry is appended.
+    assert!(!names.contains(&"BCError".to_string()));
+    assert!(names.contains(&"PBCError15".to_string()));
+    assert!(names.contains(&"PBCError17".to_string()));
+    let unique = names.iter().collect::<std::collections::HashSet<_>>();
+    assert_eq!(unique.len(), names.len());
+}
+
+/// The names of the structs and enums generated for the contract.
+fn type_names(metadata: &ContractMetadata) -> Vec<String> {
+    let code = format(generate(metadata, None).unwrap()).unwrap();
+
+    syn::parse_file(&code)
+        .unwrap()
+        .items
+        .into_iter()
+        .filter_map(|item| match item {
+            syn::Item::Struct(item) => Some(item.ident.to_string()),
+            syn::Item::Enum(item) => Some(item.ident.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The v4 fixture with a message label and a field of `Struct1` that aren't valid identifiers.
+fn unsupported_fixture() -> ContractMetadata {
+    let jsonized = std::fs::read_to_string(V4_FIXTURE)
+        .unwrap()
+        .replacen("\"label\": \"set_u32\"", "\"label\": \"set-u32\"", 1)
+        .replacen(
+            "\"name\": \"a\",\n                \"type\": 0,",
+            "\"name\": \"type\",\n                \"type\": 0,",
+            1,
+        );
+    ContractMetadata::from_json(&jsonized).unwrap()
+}
+
+#[test]
+fn test_unsupported_item_fails() {
+    let error = generate(&unsupported_fixture(), None).unwrap_err();
+
+    assert!(matches!(error, GenerateError::Item { .. }));
+    assert_eq!(
+        error.to_string(),
+        "type 12 (`test_contract::test_contract::Struct1`): `type` is not a valid identifier"
+    );
+}
+
+#[test]
+fn test_lenient_skips_unsupported_items() {
+    let (tokens, skipped) = generate_lenient(&unsupported_fixture(), None).unwrap();
+    let code = format(tokens).unwrap();
+
+    let skipped = skipped.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(
+        skipped,
+        [
+            "type 12 (`test_contract::test_contract::Struct1`): `type` is not a valid identifier",
+            "message `set-u32`: `set-u32` is not a valid identifier",
+        ]
+    );
+    assert!(code.contains("pub struct Struct1(core::marker::PhantomData<()>)"));
+    assert!(code.contains("fn get_struct1"));
+    assert!(!code.contains("set_u32"));
+    assert!(!code.contains("compile_error"));
+}
+
+#[test]
+fn test_invalid_code_hash() {
+    let mut metadata = ContractMetadata::from_file(V4_FIXTURE).unwrap();
+    metadata.code_hash = "0x1234".to_string();
+
+    assert_eq!(
+        generate_lenient(&metadata, None).unwrap_err(),
+        GenerateError::InvalidCodeHash("0x1234".to_string())
+    );
+}
+
+#[test]
+fn test_flatten_results() {
+    let metadata = ContractMetadata::from_file("tests/fixtures/psp22_nested_traits.json").unwrap();
+    let options = Options {
+        flatten_results: true,
+        ..Default::default()
+    };
+    let (tokens, _) = generate_with_options(&metadata, None, &options).unwrap();
+    let code = format(tokens).unwrap();
+
+    assert!(code.contains("ReadCall<ink_wrapper_types::Flat<u128>>"));
+    assert!(code.contains("ExecCall<ink_wrapper_types::FlatResult<(), PSP22Error>>"));
+    assert!(!code.contains("InkLangError"));
+}
+
+#[test]
+fn test_header() {
+    let metadata = ContractMetadata::from_file(V4_FIXTURE).unwrap();
+    let wrapper = format_wrapper(&metadata, generate(&metadata, None).unwrap()).unwrap();
+
+    let header = format!(
+        "// This file was auto-generated with ink-wrapper {} (https://crates.io/crates/ink-wrapper).\n\
+         // Metadata hash: 0x{}\n",
+        env!("CARGO_PKG_VERSION"),
+        hex::encode(metadata.metadata_hash)
+    );
+    assert!(wrapper.starts_with(&header));
+    assert!(syn::parse_file(&wrapper).is_ok());
+
+    let changed = std::fs::read_to_string(V4_FIXTURE).unwrap().replacen(
+        "\"label\": \"set_u32\"",
+        "\"label\": \"set_u32_\"",
+        1,
+    );
+    let changed = ContractMetadata::from_json(&changed).unwrap();
+    assert_ne!(changed.metadata_hash, metadata.metadata_hash);
+}
+
+#[test]
+fn test_diff_wrappers() {
+    let metadata = ContractMetadata::from_file(V4_FIXTURE).unwrap();
+    let wrapper = format_wrapper(&metadata, generate(&metadata, None).unwrap()).unwrap();
+
+    let reformatted = wrapper
+        .replace("    ", "\t")
+        .replace("// This file was", "// Generated");
+    assert_eq!(diff_wrappers(&reformatted, &wrapper), None);
+
+    let outdated = wrapper.replacen("fn get_u32", "fn get_u33", 1);
+    let diff = diff_wrappers(&outdated, &wrapper).unwrap();
+    assert!(diff.contains("-    pub fn get_u33"));
+    assert!(diff.contains("+    pub fn get_u32"));
+}
+
+#[test]
+fn test_config_with_shared_types() {
+    let fixtures = std::env::current_dir().unwrap().join("tests/fixtures");
+    let config = Config::from_toml(&format!(
+        r#"
+        [shared_types]
+        output = "shared_types.rs"
+        module = "crate::shared_types"
+
+        [[contract]]
+        name = "test_contract_v4"
+        metadata = "{fixtures}/test_contract_v4.json"
+        output = "test_contract_v4.rs"
+
+        [[contract]]
+        name = "psp22"
+        metadata = "{fixtures}/psp22_nested_traits.json"
+        output = "psp22.rs"
+        lenient = true
+        "#,
+        fixtures = fixtures.display()
+    ))
+    .unwrap();
+    let out_dir = tempfile::tempdir().unwrap();
+    let files = config.relative_to(out_dir.path()).generate().unwrap();
+
+    let paths = files
+        .iter()
+        .map(|file| file.path.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        ["test_contract_v4.rs", "psp22.rs", "shared_types.rs"]
+            .map(|name| out_dir.path().join(name))
+    );
+    for file in &files {
+        assert!(syn::parse_file(&file.contents).is_ok());
+        assert!(file.skipped.is_empty());
+    }
+    assert!(files[2]
+        .contents
+        .contains("// Types shared by: test_contract_v4, psp22"));
+}
+
+#[test]
+fn test_shared_types() {
+    let fixtures = std::env::current_dir().unwrap().join("tests/fixtures");
+    // The v5 fixture has the same types as the v4 one, except for `Struct1`, which gets another field.
+    let v5 = std::fs::read_to_string(V5_FIXTURE).unwrap().replacen(
+        "\"name\": \"a\",\n                \"type\": 0,",
+        "\"name\": \"z\",\n                \"type\": 0,",
+        1,
+    );
+    let out_dir = tempfile::tempdir().unwrap();
+    std::fs::write(out_dir.path().join("v5.json"), v5).unwrap();
+    let config = Config::from_toml(&format!(
+        r#"
+        [shared_types]
+        output = "shared_types.rs"
+        module = "super::shared_types"
+
+        [[contract]]
+        name = "v4"
+        metadata = "{fixtures}/test_contract_v4.json"
+        output = "v4.rs"
+
+        [[contract]]
+        name = "v5"
+        metadata = "v5.json"
+        output = "v5.rs"
+        "#,
+        fixtures = fixtures.display()
+    ))
+    .unwrap();
+    let files = config.relative_to(out_dir.path()).generate().unwrap();
+
+    let struct_names = |code: &str| {
+        syn::parse_file(code)
+            .unwrap()
+            .items
+            .into_iter()
+            .filter_map(|item| match item {
+                syn::Item::Struct(s) => Some(s.ident.to_string()),
+                syn::Item::Enum(e) => Some(e.ident.to_string()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    // `Struct2` and `Enum2` are defined the same way by both contracts, but they reference `Struct1`, which isn't.
+    let shared = struct_names(&files[2].contents);
+    assert!(shared.contains(&"Enum1".to_string()));
+    assert!(!shared.contains(&"Struct1".to_string()));
+    assert!(!shared.contains(&"Struct2".to_string()));
+    assert!(!shared.contains(&"Enum2".to_string()));
+
+    for wrapper in &files[..2] {
+        let defined = struct_names(&wrapper.contents);
+        assert!(defined.contains(&"Struct1".to_string()));
+        assert!(defined.contains(&"Struct2".to_string()));
+        assert!(defined.contains(&"Enum2".to_string()));
+        assert!(wrapper.contents.contains("pub use super::shared_types::{"));
+    }
+
+    assert_compiles(&files);
+}
+
+/// Compiles the generated files as sibling modules of a crate, panicking with the compiler's output if that fails.
+///
+/// The crate is kept in the target directory, so that its dependencies are only built once.
+fn assert_compiles(files: &[GeneratedFile]) {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("generated_check");
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    let types = std::env::current_dir()
+        .unwrap()
+        .join("../ink-wrapper-types");
+    std::fs::write(
+        dir.join("Cargo.toml"),
+        format!(
+            r#"
+            [package]
+            name = "generated-check"
+            version = "0.1.0"
+            edition = "2021"
+
+            [dependencies]
+            ink-wrapper-types = {{ path = "{types}", default-features = false }}
+            ink_primitives = "4.3.0"
+            scale = {{ package = "parity-scale-codec", version = "3", default-features = false, features = ["derive"] }}
+
+            [workspace]
+            "#,
+            types = types.display()
+        ),
+    )
+    .unwrap();
+
+    let mut lib = String::from("#![allow(dead_code)]\n");
+    for file in files {
+        let name = file.path.file_name().unwrap();
+        std::fs::write(dir.join("src").join(name), &file.contents).unwrap();
+        lib.push_str(&format!(
+            "mod {};\n",
+            file.path.file_stem().unwrap().to_str().unwrap()
+        ));
+    }
+    std::fs::write(dir.join("src/lib.rs"), lib).unwrap();
+
+    let output = std::process::Command::new(env!("CARGO"))
+        .arg("check")
+        .arg("--quiet")
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "The generated files don't compile:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// The v4 fixture turned into a `.contract` bundle with the given WASM, with `source.hash` set to `hash`.
+fn bundle(wasm: &[u8], hash: [u8; 32]) -> String {
+    std::fs::read_to_string(V4_FIXTURE).unwrap().replacen(
+        "\"hash\": \"0xb32ad224e14afe41a2356a29038647ada47c131ba550e9c70c2859d8367e2a3c\",",
+        &format!(
+            "\"hash\": \"0x{}\", \"wasm\": \"0x{}\",",
+            hex::encode(hash),
+            hex::encode(wasm)
+        ),
+        1,
+    )
+}
+
+#[test]
+fn test_contract_bundle() {
+    let wasm = b"\0asm\x01\0\0\0";
+    let metadata = ContractMetadata::from_json(&bundle(wasm, code_hash(wasm))).unwrap();
+    assert_eq!(metadata.wasm.as_deref(), Some(&wasm[..]));
+
+    let code = format(generate(&metadata, None).unwrap()).unwrap();
+    assert!(code.contains("pub fn upload()"));
+    assert!(code.contains("let wasm = b\"\\0asm\\x01\\0\\0\\0\";"));
+
+    let code = format(generate(&metadata, Some("my_contract.wasm".to_string())).unwrap()).unwrap();
+    assert!(code.contains("include_bytes!(\"my_contract.wasm\")"));
+}
+
+#[test]
+fn test_contract_bundle_hash_mismatch() {
+    let wasm = b"\0asm\x01\0\0\0";
+    let error = ContractMetadata::from_json(&bundle(wasm, [0; 32]))
+        .err()
+        .unwrap();
+
+    assert!(error.to_string().contains("doesn't match source.hash"));
+}
+
+#[test]
+fn test_builder_rejects_embed_wasm_with_wasm_path() {
+    let error = Builder::new("tests/fixtures/test_contract_v4.contract")
+        .wasm_path("tests/fixtures/test_contract.wasm")
+        .embed_wasm(true)
+        .generate()
+        .unwrap_err();
+
+    assert!(error.to_string().contains("can't be used together"));
+}
+
+#[test]
+fn test_verify_wasm() {
+    let wasm = b"\0asm\x01\0\0\0";
+    let metadata = ContractMetadata::from_json(&bundle(wasm, code_hash(wasm))).unwrap();
+    let out_dir = tempfile::tempdir().unwrap();
+    let wasm_path = out_dir.path().join("my_contract.wasm");
+
+    std::fs::write(&wasm_path, wasm).unwrap();
+    assert!(metadata.verify_wasm(&wasm_path).is_ok());
+
+    std::fs::write(&wasm_path, b"\0asm\x01\0\0\0\0").unwrap();
+    let error = metadata.verify_wasm(&wasm_path).unwrap_err();
+    assert!(format!("{:#}", error).contains("doesn't match source.hash"));
+
+    let config = Config::from_toml(&format!(
+        r#"
+        [[contract]]
+        name = "my_contract"
+        metadata = "{fixture}"
+        wasm_path = "my_contract.wasm"
+        output = "my_contract.rs"
+        "#,
+        fixture = std::env::current_dir().unwrap().join(V4_FIXTURE).display()
+    ))
+    .unwrap();
+    let error = config.relative_to(out_dir.path()).generate().unwrap_err();
+    assert!(format!("{:#}", error).contains("doesn't match source.hash"));
+}
@@ -0,0 +1,53 @@
+use anyhow::Result;
+use assert2::assert;
+use drink::{runtime::MinimalRuntime, session::Session};
+use futures::executor::block_on;
+use ink_wrapper_types::{AsyncAdapter, LocalAsyncConnection, ToAccountId};
+
+use crate::{
+    test_contract::{self, Instance},
+    *,
+};
+
+/// Written against `LocalAsyncConnection` only, so it would run against a live chain just as well.
+async fn increment_u32<C: LocalAsyncConnection>(
+    connection: &C,
+    instance: Instance,
+) -> Result<(u32, u32)> {
+    let old_val = connection.query(instance.get_u32()).await?.result?;
+    connection
+        .execute(instance.set_u32(old_val + 42))
+        .await?
+        .result?;
+    let new_val = connection.query(instance.get_u32()).await?.result?;
+
+    Ok((old_val, new_val))
+}
+
+#[test]
+fn test_async_adapter() -> Result<()> {
+    let connection: AsyncAdapter<_, MinimalRuntime> = Session::<MinimalRuntime>::new()
+        .expect("Init new Session")
+        .into();
+    connection.borrow_mut().set_actor(BOB);
+
+    block_on(async {
+        let code_hash = connection
+            .upload_code(test_contract::upload())
+            .await?
+            .code_hash;
+        assert!(code_hash.as_ref() == test_contract::CODE_HASH);
+
+        let instance: Instance = connection
+            .instantiate(Instance::default())
+            .await?
+            .result
+            .to_account_id()
+            .into();
+
+        let (old_val, new_val) = increment_u32(&connection, instance).await?;
+        assert!(new_val == old_val + 42);
+
+        Ok(())
+    })
+}
@@ -0,0 +1,288 @@
+use anyhow::Result;
+use assert2::assert;
+use drink::session::Session;
+use ink_primitives::AccountId;
+use ink_wrapper_types::{ChainEvent, Connection, ContractEvents, ContractsEvent, ToAccountId};
+
+use self::runtime::CustomRuntime;
+use crate::{
+    test_contract::{self, event::Event, Instance},
+    *,
+};
+
+/// A runtime defined outside of drink, which differs from `MinimalRuntime` in the ways a real chain would: it has a
+/// pallet of its own, its pallets are at other indices and the existential deposit and storage deposits differ.
+mod runtime {
+    use std::time::SystemTime;
+
+    use drink::{
+        frame_support::{
+            construct_runtime, parameter_types,
+            sp_runtime::{
+                testing::H256,
+                traits::{BlakeTwo256, Convert, Dispatchable, IdentityLookup},
+                AccountId32, BuildStorage, Perbill, Storage,
+            },
+            traits::{ConstBool, ConstU128, ConstU32, ConstU64, Currency, Hooks, Randomness},
+            weights::Weight,
+        },
+        frame_system, pallet_balances, pallet_contracts, pallet_timestamp,
+        runtime::{
+            pallet_contracts_debugging::DrinkDebug, AccountIdFor, Runtime, RuntimeMetadataPrefixed,
+        },
+    };
+
+    /// A pallet that does nothing, taking the index that `MinimalRuntime` gives to `Balances`.
+    // The pallet macro generates helpers the runtime doesn't use for a pallet without calls.
+    #[allow(dead_code)]
+    #[frame_support::pallet]
+    pub mod pallet_noop {
+        #[pallet::pallet]
+        pub struct Pallet<T>(_);
+
+        #[pallet::config]
+        pub trait Config: frame_system::Config {}
+    }
+
+    construct_runtime!(
+        pub enum CustomRuntime {
+            System: frame_system,
+            Noop: pallet_noop,
+            Timestamp: pallet_timestamp,
+            Contracts: pallet_contracts,
+            Balances: pallet_balances,
+        }
+    );
+
+    /// The balance of the default actor and of the accounts used by the tests.
+    pub const INITIAL_BALANCE: u128 = 1_000_000_000_000_000;
+
+    pub const EXISTENTIAL_DEPOSIT: u128 = 1_000;
+
+    impl frame_system::Config for CustomRuntime {
+        type BaseCallFilter = frame_support::traits::Everything;
+        type BlockWeights = ();
+        type BlockLength = ();
+        type Block = frame_system::mocking::MockBlockU32<CustomRuntime>;
+        type RuntimeOrigin = RuntimeOrigin;
+        type RuntimeCall = RuntimeCall;
+        type Nonce = u32;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = AccountId32;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type RuntimeEvent = RuntimeEvent;
+        type BlockHashCount = ConstU32<250>;
+        type DbWeight = ();
+        type Version = ();
+        type PalletInfo = PalletInfo;
+        type AccountData = pallet_balances::AccountData<u128>;
+        type OnNewAccount = ();
+        type OnKilledAccount = ();
+        type SystemWeightInfo = ();
+        type SS58Prefix = ();
+        type OnSetCode = ();
+        type MaxConsumers = ConstU32<16>;
+    }
+
+    impl pallet_noop::Config for CustomRuntime {}
+
+    impl pallet_balances::Config for CustomRuntime {
+        type RuntimeEvent = RuntimeEvent;
+        type WeightInfo = ();
+        type Balance = u128;
+        type DustRemoval = ();
+        type ExistentialDeposit = ConstU128<EXISTENTIAL_DEPOSIT>;
+        type AccountStore = System;
+        type ReserveIdentifier = [u8; 8];
+        type FreezeIdentifier = ();
+        type MaxLocks = ();
+        type MaxReserves = ();
+        type MaxHolds = ConstU32<1>;
+        type MaxFreezes = ();
+        type RuntimeHoldReason = RuntimeHoldReason;
+    }
+
+    impl pallet_timestamp::Config for CustomRuntime {
+        type Moment = u64;
+        type OnTimestampSet = ();
+        type MinimumPeriod = ConstU64<1>;
+        type WeightInfo = ();
+    }
+
+    pub enum SandboxRandomness {}
+
+    impl Randomness<H256, u32> for SandboxRandomness {
+        fn random(_subject: &[u8]) -> (H256, u32) {
+            unreachable!("No randomness")
+        }
+    }
+
+    type BalanceOf = <Balances as Currency<AccountId32>>::Balance;
+
+    impl Convert<Weight, BalanceOf> for CustomRuntime {
+        fn convert(weight: Weight) -> BalanceOf {
+            weight.ref_time().into()
+        }
+    }
+
+    parameter_types! {
+        pub SandboxSchedule: pallet_contracts::Schedule<CustomRuntime> = Default::default();
+        pub DefaultDepositLimit: BalanceOf = 100_000_000;
+        pub CodeHashLockupDepositPercent: Perbill = Perbill::from_percent(10);
+        pub MaxDelegateDependencies: u32 = 32;
+    }
+
+    impl pallet_contracts::Config for CustomRuntime {
+        type Time = Timestamp;
+        type Randomness = SandboxRandomness;
+        type Currency = Balances;
+        type RuntimeEvent = RuntimeEvent;
+        type RuntimeCall = RuntimeCall;
+        type CallFilter = ();
+        type WeightPrice = Self;
+        type WeightInfo = ();
+        type ChainExtension = ();
+        type Schedule = SandboxSchedule;
+        type CallStack = [pallet_contracts::Frame<Self>; 5];
+        type DepositPerByte = ConstU128<10>;
+        type DepositPerItem = ConstU128<100>;
+        type AddressGenerator = pallet_contracts::DefaultAddressGenerator;
+        type MaxCodeLen = ConstU32<{ 123 * 1024 }>;
+        type MaxStorageKeyLen = ConstU32<128>;
+        type UnsafeUnstableInterface = ConstBool<false>;
+        type MaxDebugBufferLen = ConstU32<{ 2 * 1024 * 1024 }>;
+        type Migrations = ();
+        type DefaultDepositLimit = DefaultDepositLimit;
+        type Debug = DrinkDebug;
+        type CodeHashLockupDepositPercent = CodeHashLockupDepositPercent;
+        type MaxDelegateDependencies = MaxDelegateDependencies;
+        type RuntimeHoldReason = RuntimeHoldReason;
+        type Environment = ();
+    }
+
+    impl Runtime for CustomRuntime {
+        fn initialize_storage(storage: &mut Storage) -> Result<(), String> {
+            pallet_balances::GenesisConfig::<Self> {
+                balances: [Self::default_actor(), crate::BOB]
+                    .into_iter()
+                    .map(|account| (account, INITIAL_BALANCE))
+                    .collect(),
+            }
+            .assimilate_storage(storage)
+        }
+
+        fn initialize_block(
+            height: frame_system::pallet_prelude::BlockNumberFor<Self>,
+            parent_hash: H256,
+        ) -> Result<(), String> {
+            System::reset_events();
+            System::initialize(&height, &parent_hash, &Default::default());
+
+            Balances::on_initialize(height);
+            Timestamp::set_timestamp(
+                SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .expect("Time went backwards")
+                    .as_secs(),
+            );
+            Timestamp::on_initialize(height);
+            Contracts::on_initialize(height);
+
+            System::note_finished_initialize();
+
+            Ok(())
+        }
+
+        fn finalize_block(
+            height: frame_system::pallet_prelude::BlockNumberFor<Self>,
+        ) -> Result<H256, String> {
+            Contracts::on_finalize(height);
+            Timestamp::on_finalize(height);
+            Balances::on_finalize(height);
+
+            Ok(System::finalize().hash())
+        }
+
+        fn default_actor() -> AccountIdFor<Self> {
+            AccountId32::new([7u8; 32])
+        }
+
+        fn get_metadata() -> RuntimeMetadataPrefixed {
+            Self::metadata()
+        }
+
+        fn convert_account_to_origin(
+            account: AccountIdFor<Self>,
+        ) -> <<Self as frame_system::Config>::RuntimeCall as Dispatchable>::RuntimeOrigin {
+            Some(account).into()
+        }
+    }
+}
+
+fn setup(caller: AccountId32) -> (Session<CustomRuntime>, Instance) {
+    let mut session = Session::<CustomRuntime>::new().expect("Init new Session");
+    let _code_hash = session.upload_code(test_contract::upload()).unwrap();
+
+    let _ = session.set_actor(caller);
+
+    let address = session
+        .instantiate(Instance::default())
+        .unwrap()
+        .result
+        .to_account_id()
+        .into();
+
+    (session, address)
+}
+
+#[test]
+fn test_messages_on_custom_runtime() -> Result<()> {
+    let (mut session, instance) = setup(BOB);
+
+    let old_val = session.query(instance.get_u32())?.result?;
+    session.execute(instance.set_u32(old_val + 42))?.result?;
+    let new_val = session.query(instance.get_u32())?.result?;
+    assert!(new_val == old_val + 42);
+
+    Ok(())
+}
+
+#[test]
+fn test_events_on_custom_runtime() -> Result<()> {
+    let (mut session, instance) = setup(BOB);
+
+    let txn = session.execute(instance.generate_events())?;
+    let events = ContractEvents::from_iter(&txn.events, instance);
+
+    assert!(events.len() == 2);
+    assert!(events[1] == Ok(Event::Event2 {}));
+
+    Ok(())
+}
+
+#[test]
+fn test_value_transfers_on_custom_runtime() -> Result<()> {
+    let (mut session, instance) = setup(BOB);
+    let address: AccountId = instance.into();
+
+    let result = session.execute(instance.receive_value().with_value(123))?;
+    assert!(result.runtime_events.contains(&ChainEvent::Transfer {
+        from: bob(),
+        to: address,
+        amount: 123,
+    }));
+    assert!(result
+        .runtime_events
+        .contains(&ChainEvent::Contracts(ContractsEvent::Called {
+            caller: Some(bob()),
+            contract: address,
+        })));
+
+    let balance = session
+        .sandbox()
+        .free_balance(&AccountId32::new(*address.as_ref()));
+    assert!(balance == runtime::EXISTENTIAL_DEPOSIT + 123);
+
+    Ok(())
+}
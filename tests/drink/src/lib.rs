@@ -1,6 +1,48 @@
+// The generated wrappers are formatted by `ink-wrapper` itself, so rustfmt leaves them alone.
+#[rustfmt::skip]
 mod psp22_contract;
+#[rustfmt::skip]
 mod test_contract;
 
+ink_wrapper_macro::contract!(
+    mod test_contract_macro,
+    metadata = "../test_contract/target/ink/test_contract.json",
+    wasm = "../test_contract/target/ink/test_contract.wasm"
+);
+
+// The test contract built with ink! 5, used to check the event decoding of wrappers generated from version 5 metadata.
+ink_wrapper_macro::contract!(
+    mod test_contract_v5,
+    metadata = "../../ink-wrapper/tests/fixtures/test_contract_v5.json"
+);
+
+// The v4 test contract as a `.contract` bundle, with its (dummy) WASM embedded in `upload()`.
+ink_wrapper_macro::contract!(
+    mod test_contract_bundle,
+    metadata = "../../ink-wrapper/tests/fixtures/test_contract_v4.contract",
+    embed_wasm = true
+);
+
+// The PSP22 contract with its messages relabeled into nested trait namespaces, like the ones openbrush produces.
+ink_wrapper_macro::contract!(
+    mod psp22_nested_traits,
+    metadata = "../../ink-wrapper/tests/fixtures/psp22_nested_traits.json"
+);
+
+// The PSP22 contract with messages returning flattened results.
+ink_wrapper_macro::contract!(
+    mod psp22_flat,
+    metadata = "../psp22_contract/target/ink/psp22_contract.json",
+    wasm = "../psp22_contract/target/ink/psp22_contract.wasm",
+    flatten_results = true
+);
+
+#[cfg(test)]
+mod async_tests;
+#[cfg(test)]
+mod custom_runtime_tests;
+#[cfg(test)]
+mod macro_tests;
 #[cfg(test)]
 mod psp22_tests;
 #[cfg(test)]
@@ -0,0 +1,38 @@
+use anyhow::Result;
+use assert2::assert;
+use drink::{runtime::MinimalRuntime, session::Session};
+use ink_wrapper_types::{Connection, ToAccountId};
+
+use crate::{test_contract, test_contract_macro::Instance, *};
+
+#[test]
+fn test_macro_generated_wrapper() -> Result<()> {
+    let mut session: Session<MinimalRuntime> = Session::new().expect("Init new Session");
+    let code_hash = session
+        .upload_code(test_contract_macro::upload())
+        .unwrap()
+        .code_hash;
+    assert!(code_hash.as_ref() == test_contract::CODE_HASH);
+
+    let _ = session.set_actor(BOB);
+
+    let instance: Instance = session
+        .instantiate(Instance::default())
+        .unwrap()
+        .result
+        .to_account_id()
+        .into();
+
+    let _r = session.execute(instance.set_u32(42)).unwrap();
+
+    assert!(session.query(instance.get_u32()).unwrap().result.unwrap() == 42);
+
+    Ok(())
+}
+
+#[test]
+fn test_macro_embedded_wasm() {
+    let upload = test_contract_bundle::upload();
+    assert!(upload.wasm == b"\0asm\x01\0\0\0");
+    assert!(upload.expected_code_hash == test_contract_bundle::CODE_HASH);
+}
@@ -1,8 +1,11 @@
 use anyhow::Result;
 use assert2::assert;
-use drink::{runtime::MinimalRuntime, session::Session, AccountId32};
+use drink::{runtime::MinimalRuntime, session::Session, AccountId32, Weight};
 use ink_primitives::AccountId;
-use ink_wrapper_types::{Connection, ToAccountId};
+use ink_wrapper_types::{
+    CallError, CallResultExt, Connection, ContractsError, DispatchFailure, Error, GasEstimationExt,
+    StrictExecutionExt, ToAccountId,
+};
 use psp22_contract::{Instance, PSP22 as _};
 
 use crate::*;
@@ -20,7 +23,7 @@ pub fn balance_of(
 }
 
 pub fn setup(caller: AccountId32) -> (Session<MinimalRuntime>, Instance) {
-    let mut session = Session::new().expect("Init new Session");
+    let mut session: Session<MinimalRuntime> = Session::new().expect("Init new Session");
     let _code_hash = session.upload_code(psp22_contract::upload()).unwrap();
 
     let _ = session.set_actor(caller);
@@ -99,3 +102,159 @@ fn test_burn() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_transfer_as_caller() -> Result<()> {
+    let (mut session, instance) = setup(BOB);
+    session.execute(instance.transfer(alice(), 100, vec![]))?;
+
+    session.execute(instance.transfer(bob(), 30, vec![]).as_caller(alice()))?;
+
+    assert!(balance_of(&mut session, instance, alice()) == 70);
+    assert!(session.get_actor() == BOB);
+
+    Ok(())
+}
+
+#[test]
+fn test_gas_limit() -> Result<()> {
+    let (mut session, instance) = setup(BOB);
+
+    let result = session.execute(
+        instance
+            .transfer(alice(), 100, vec![])
+            .with_gas_limit(Weight::from_parts(1, 1)),
+    );
+
+    let Err(error) = result else {
+        panic!("Expected the call to fail");
+    };
+    assert!(
+        error.to_string()
+            == "Contract call failed: OutOfGas: the executed contract exhausted its gas limit"
+    );
+    assert!(let Error::CallFailed(DispatchFailure::Contracts(ContractsError::OutOfGas)) = error);
+    assert!(balance_of(&mut session, instance, alice()) == 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_storage_deposit_limit() -> Result<()> {
+    let (mut session, instance) = setup(BOB);
+
+    // Alice has no balance yet, so the transfer needs to pay for a new storage item.
+    let result = session.execute(
+        instance
+            .transfer(alice(), 100, vec![])
+            .with_storage_deposit_limit(0),
+    );
+    assert!(
+        let Err(Error::CallFailed(DispatchFailure::Contracts(
+            ContractsError::StorageDepositLimitExhausted
+        ))) = result
+    );
+
+    let result = session.query(instance.balance_of(alice()).with_storage_deposit_limit(0))?;
+    assert!(result.result? == 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_gas_estimation() -> Result<()> {
+    let (session, instance) = setup(BOB);
+    let mut session = session.with_gas_estimation(10);
+
+    let call = instance.transfer(alice(), 100, vec![]);
+    let gas_required = session.query(&call)?.gas_required;
+    let estimate = session.estimate_gas(&call)?;
+    assert!(estimate.ref_time() == gas_required.ref_time() * 110 / 100);
+    assert!(estimate.proof_size() == gas_required.proof_size() * 110 / 100);
+
+    assert!(session.execute(call)?.result? == Ok(()));
+    assert!(balance_of(&mut session, instance, alice()) == 100);
+
+    // An explicit gas limit takes precedence over the estimation.
+    let result = session.execute(
+        instance
+            .transfer(alice(), 100, vec![])
+            .with_gas_limit(Weight::from_parts(1, 1)),
+    );
+    assert!(let Err(Error::CallFailed(_)) = result);
+
+    Ok(())
+}
+
+#[test]
+fn test_strict_execution() -> Result<()> {
+    let (session, instance) = setup(BOB);
+    let mut session = session.with_strict_execution();
+
+    assert!(
+        session
+            .execute(instance.transfer(alice(), 100, vec![]))?
+            .result?
+            == Ok(())
+    );
+
+    let result = session.execute(instance.transfer(alice(), 10_000, vec![]));
+    let Err(Error::Reverted { data, error, .. }) = result else {
+        panic!("Expected the call to revert, got {result:?}");
+    };
+    assert!(data == [0, 1, 1]);
+    assert!(format!("{:?}", error.unwrap()) == "InsufficientBalance");
+    assert!(balance_of(&mut session, instance, alice()) == 100);
+
+    // Queries of reverting messages still return their result.
+    let result = session.query(instance.transfer(alice(), 10_000, vec![]))?;
+    assert!(result.reverted);
+
+    Ok(())
+}
+
+#[test]
+fn test_nested_trait_namespaces() -> Result<()> {
+    use psp22_nested_traits::psp22::{extensions::burnable::PSP22Burnable as _, PSP22 as _};
+
+    let (mut session, instance) = setup(BOB);
+    let instance: psp22_nested_traits::Instance = AccountId::from(instance).into();
+
+    session.execute(instance.transfer(alice(), 100, vec![]))?;
+    session.execute(instance.burn(50))?;
+
+    assert!(session.query(instance.balance_of(alice()))?.result? == 100);
+    assert!(session.query(instance.total_supply())?.result? == 950);
+
+    Ok(())
+}
+
+#[test]
+fn test_flattened_results() -> Result<()> {
+    use psp22_flat::PSP22 as _;
+
+    let mut session = Session::<MinimalRuntime>::new().unwrap();
+    session.upload_code(psp22_flat::upload()).unwrap();
+    let _ = session.set_actor(BOB);
+    let instance: psp22_flat::Instance = session
+        .instantiate(psp22_flat::Instance::new(1000))
+        .unwrap()
+        .result
+        .to_account_id()
+        .into();
+
+    let balance = session.query(instance.balance_of(bob().into())).outcome()?;
+    assert!(balance == 1000);
+
+    session
+        .execute(instance.transfer(alice().into(), 100, vec![]))
+        .outcome()?;
+    assert!(session.query(instance.balance_of(alice().into()))?.ok()? == 100);
+
+    let result = session
+        .execute(instance.transfer(alice().into(), 10_000, vec![]))
+        .outcome();
+    assert!(let Err(CallError::Contract(psp22_flat::PSP22Error::InsufficientBalance())) = result);
+
+    Ok(())
+}
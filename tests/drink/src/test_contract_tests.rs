@@ -2,15 +2,18 @@ use anyhow::Result;
 use assert2::assert;
 use drink::{runtime::MinimalRuntime, session::Session, AccountId32};
 use ink_primitives::AccountId;
-use ink_wrapper_types::{Connection, ContractEvents, ToAccountId};
+use ink_wrapper_types::{
+    ChainEvent, Connection, ContractEvents, ContractsEvent, DebugOutput, DebugOutputExt, Error,
+    StrictExecutionExt, ToAccountId,
+};
 
 use crate::{
-    test_contract::{self, Enum1, Instance, Struct1, Struct2},
+    test_contract::{self, Either, Entry, Enum1, Instance, NestedStruct1, Pair, Struct1, Struct2},
     *,
 };
 
 fn setup(caller: AccountId32) -> (Session<MinimalRuntime>, Instance) {
-    let mut session = Session::new().expect("Init new Session");
+    let mut session: Session<MinimalRuntime> = Session::new().expect("Init new Session");
     let _code_hash = session.upload_code(test_contract::upload()).unwrap();
 
     let _ = session.set_actor(caller);
@@ -65,6 +68,127 @@ fn test_struct_messages() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_generic_type_messages() -> Result<()> {
+    let (mut session, instance) = setup(BOB);
+
+    let pair = Pair {
+        a: 1,
+        b: 2,
+        count: 0,
+    };
+    let got = session.query(instance.swap_pair(pair))?.result?;
+    assert!(
+        got == Pair {
+            a: 2,
+            b: 1,
+            count: 1
+        }
+    );
+
+    let pair = Pair {
+        a: true,
+        b: false,
+        count: 5,
+    };
+    let got = session.query(instance.swap_bool_pair(pair))?.result?;
+    assert!(
+        got == Pair {
+            a: false,
+            b: true,
+            count: 6
+        }
+    );
+
+    session.execute(instance.set_u32(7))?;
+    let got = session.query(instance.get_either(true))?.result?;
+    assert!(got == Either::Left(7));
+    let got = session.query(instance.get_either(false))?.result?;
+    assert!(
+        got == Either::Right(vec![Pair {
+            a: 7,
+            b: 7,
+            count: 0
+        }])
+    );
+
+    let got = session
+        .query(instance.swap_either(Either::Left(3)))?
+        .result?;
+    assert!(got == Either::Right(3));
+
+    let entry = Entry {
+        key: 1,
+        value: 2,
+        version: (1, 0),
+    };
+    let got = session.query(instance.swap_entry(entry))?.result?;
+    assert!(
+        got == Entry {
+            key: 2,
+            value: 1,
+            version: (2, 1)
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_clashing_type_names() -> Result<()> {
+    let (mut session, instance) = setup(BOB);
+
+    session.execute(instance.set_nested_struct1(NestedStruct1 { value: true }))?;
+    let got = session.query(instance.get_nested_struct1())?.result?;
+    assert!(got == NestedStruct1 { value: true });
+    assert!(session.query(instance.get_bool())?.result?);
+
+    Ok(())
+}
+
+#[test]
+fn test_bit_sequence_messages() -> Result<()> {
+    use ink_wrapper_types::bitvec::{bitvec, order::Lsb0, order::Msb0};
+
+    let (mut session, instance) = setup(BOB);
+
+    let bits = bitvec![u8, Lsb0; 1, 0, 0, 1, 1];
+    let got = session.query(instance.invert_bits(bits))?.result?;
+    assert!(got == bitvec![u8, Lsb0; 0, 1, 1, 0, 0]);
+
+    let bits = bitvec![u32, Msb0; 1, 1, 0];
+    let got = session.query(instance.reverse_bits(bits))?.result?;
+    assert!(got == bitvec![u32, Msb0; 0, 1, 1]);
+
+    Ok(())
+}
+
+#[test]
+fn test_256_bit_integer_messages() -> Result<()> {
+    use ink_wrapper_types::{I256, U256};
+
+    let (mut session, instance) = setup(BOB);
+
+    let got = session.query(instance.get_u256(u128::MAX))?.result?;
+    assert!(got == U256::from(u128::MAX));
+
+    for value in [0, 42, -42, i128::MIN, i128::MAX] {
+        let got = session.query(instance.get_i256(value))?.result?;
+        assert!(got == I256::from(value));
+        assert!(i128::try_from(got) == Ok(value));
+
+        let got = session
+            .query(instance.truncate_i256(I256::from(value)))?
+            .result?;
+        assert!(got == value);
+    }
+
+    assert!(i128::try_from(I256(U256::from(u128::MAX))).is_err());
+    assert!(i128::try_from(I256(!U256::from(u128::MAX))).is_err());
+
+    Ok(())
+}
+
 #[test]
 fn test_array_messages() -> Result<()> {
     let (mut session, instance) = setup(BOB);
@@ -188,6 +312,61 @@ fn test_events() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_event_signature_topics() -> Result<()> {
+    use test_contract::event::{self, Event};
+
+    let (mut session, instance) = setup(BOB);
+
+    let txn = session.execute(instance.generate_events())?;
+    assert!(txn.events[0].topics[0] == event::EVENT1_SIGNATURE_TOPIC);
+    assert!(txn.events[1].topics[0] == event::EVENT2_SIGNATURE_TOPIC);
+
+    let txn = session.execute(instance.generate_long_signature_event())?;
+    assert!(txn.events[0].topics[0] == event::EVENT_WITH_A_LONG_SIGNATURE_SIGNATURE_TOPIC);
+    // The signature topic is followed by the topic of the `value` field.
+    assert!(txn.events[0].topics.len() == 2);
+
+    let events = ContractEvents::from_iter(&txn.events, instance);
+    assert!(events == vec![Ok(Event::EventWithALongSignature { value: 0 })]);
+
+    Ok(())
+}
+
+#[test]
+fn test_events_by_signature() -> Result<()> {
+    use psp22_contract::PSP22 as _;
+
+    let (mut session, instance) = setup(BOB);
+    session.upload_code(psp22_contract::upload())?;
+    let token: psp22_contract::Instance = session
+        .instantiate(psp22_contract::Instance::new(1000))?
+        .result
+        .to_account_id()
+        .into();
+
+    let mut events = session.execute(instance.generate_events())?.events;
+    events.extend(
+        session
+            .execute(token.transfer(alice(), 100, vec![]))?
+            .events,
+    );
+    let events = ContractEvents { events };
+
+    let transfers = events.by_signature::<psp22_contract::event::Event>();
+    assert!(transfers.len() == 1);
+    assert!(transfers[0].0 == token.into());
+    assert!(let Ok(psp22_contract::event::Event::Transfer { value: 100, .. }) = transfers[0].1);
+
+    let test_events = events.by_signature::<test_contract::event::Event>();
+    assert!(test_events.len() == 2);
+    assert!(test_events
+        .iter()
+        .all(|(emitter, _)| *emitter == instance.into()));
+
+    Ok(())
+}
+
 #[test]
 fn test_ink_lang_error() -> Result<()> {
     let (mut session, instance) = setup(BOB);
@@ -205,8 +384,21 @@ fn test_ink_lang_error() -> Result<()> {
 #[test]
 fn test_upload() -> Result<()> {
     let mut session: Session<MinimalRuntime> = Session::new().expect("Init new Session");
-    let code_hash = session.upload_code(test_contract::upload()).unwrap();
-    assert!(code_hash.as_ref() == test_contract::CODE_HASH);
+    session.set_actor(BOB);
+    let result = session.upload_code(test_contract::upload()).unwrap();
+    assert!(result.code_hash.as_ref() == test_contract::CODE_HASH);
+    assert!(result.runtime_events.iter().any(|event| matches!(
+        event,
+        ChainEvent::Contracts(ContractsEvent::CodeStored { code_hash, uploader, .. })
+            if *code_hash == test_contract::CODE_HASH && *uploader == bob()
+    )));
+
+    // Uploading the same code again succeeds, but doesn't store it again.
+    let result = session.upload_code(test_contract::upload()).unwrap();
+    assert!(!result.runtime_events.iter().any(|event| matches!(
+        event,
+        ChainEvent::Contracts(ContractsEvent::CodeStored { .. })
+    )));
     Ok(())
 }
 
@@ -225,6 +417,25 @@ fn test_receiving_value() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_payable_read_messages() -> Result<()> {
+    let (mut session, instance) = setup(BOB);
+    let contract = AccountId32::new(*AccountId::from(instance).as_ref());
+    let bob_balance = session.sandbox().free_balance(&BOB);
+    let contract_balance = session.sandbox().free_balance(&contract);
+
+    let transferred = session
+        .query(instance.get_transferred_value().with_value(123))?
+        .result?;
+    assert!(transferred == 123);
+
+    // Queries are dry runs, so nothing was actually transferred.
+    assert!(session.sandbox().free_balance(&BOB) == bob_balance);
+    assert!(session.sandbox().free_balance(&contract) == contract_balance);
+
+    Ok(())
+}
+
 #[test]
 fn test_receiving_value_in_constructor() -> Result<()> {
     let mut session: Session<MinimalRuntime> = Session::new().expect("Init new Session");
@@ -244,3 +455,92 @@ fn test_receiving_value_in_constructor() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_v5_event_decoding() {
+    use ink_wrapper_types::EventDecode as _;
+    use scale::Encode as _;
+    use test_contract_v5::event::{self, Event};
+
+    // ink! 5 encodes only the fields of an event, which is identified by its signature topic instead.
+    let data = 42u128.encode();
+    let topics = [event::RECEIVED_SIGNATURE_TOPIC];
+    assert!(Event::decode_event(&topics, &data) == Ok(Event::Received { value: 42 }));
+
+    let topics = [event::EVENT2_SIGNATURE_TOPIC];
+    assert!(Event::decode_event(&topics, &[]) == Ok(Event::Event2 {}));
+
+    let topics = [[0u8; 32]];
+    assert!(Event::decode_event(&topics, &data).is_err());
+    assert!(Event::match_signature(&[event::RECEIVED_SIGNATURE_TOPIC]) == Some("Received"));
+}
+
+#[test]
+fn test_debug_lines() -> Result<()> {
+    let (session, instance) = setup(BOB);
+    let mut session = session.with_debug_output(DebugOutput::Stderr);
+
+    let result = session.query(instance.print_debug_lines(2))?;
+    assert!(result.debug_lines() == ["line 0", "line 1"]);
+
+    let result = session.execute(instance.print_debug_lines(1))?;
+    assert!(result.debug_lines() == ["line 0"]);
+
+    let result = session.execute(instance.print_debug_lines(0))?;
+    assert!(result.debug_lines().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_debug_lines_of_reverted_calls() -> Result<()> {
+    let (session, instance) = setup(BOB);
+    let mut session = session
+        .with_strict_execution()
+        .with_debug_output(DebugOutput::Stderr);
+
+    let result = session.execute(instance.print_debug_lines_and_fail(2));
+    let Err(err @ Error::Reverted { .. }) = result else {
+        panic!("Expected the call to revert, got {result:?}");
+    };
+    assert!(err.debug_message() == Some("line 0\nline 1\n"));
+
+    Ok(())
+}
+
+#[test]
+fn test_runtime_events() -> Result<()> {
+    let mut session: Session<MinimalRuntime> = Session::new().expect("Init new Session");
+    session.upload_code(test_contract::upload())?;
+    session.set_actor(BOB);
+
+    let result = session.instantiate(Instance::default())?;
+    let address: AccountId = result.result.to_account_id();
+    assert!(result
+        .runtime_events
+        .contains(&ChainEvent::Contracts(ContractsEvent::Instantiated {
+            deployer: bob(),
+            contract: address,
+        })));
+
+    assert!(!result.runtime_events.iter().any(|event| matches!(
+        event,
+        ChainEvent::Contracts(ContractsEvent::CodeStored { .. })
+    )));
+
+    let instance: Instance = address.into();
+    let result = session.execute(instance.receive_value().with_value(123))?;
+    assert!(result.runtime_events.contains(&ChainEvent::Transfer {
+        from: bob(),
+        to: address,
+        amount: 123,
+    }));
+    assert!(result
+        .runtime_events
+        .contains(&ChainEvent::Contracts(ContractsEvent::Called {
+            caller: Some(bob()),
+            contract: address,
+        })));
+
+    Ok(())
+}
@@ -0,0 +1,12 @@
+//! Tests against a live chain. They expect a `substrate-contracts-node` listening on `ws://127.0.0.1:9944`.
+
+ink_wrapper_macro::contract!(
+    mod test_contract,
+    metadata = "../test_contract/target/ink/test_contract.json",
+    wasm = "../test_contract/target/ink/test_contract.wasm"
+);
+
+#[cfg(test)]
+mod test_contract_tests;
+
+pub const NODE_URL: &str = "ws://127.0.0.1:9944";
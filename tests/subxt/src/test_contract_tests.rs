@@ -0,0 +1,116 @@
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use assert2::assert;
+use ink_wrapper_types::{
+    subxt_types::SignedConnection, AsyncConnection, ContractEvents, ToAccountId, TxStatus,
+};
+use subxt::PolkadotConfig;
+use subxt_signer::sr25519::{dev, Keypair};
+
+use crate::{test_contract::Instance, *};
+
+type Connection = SignedConnection<PolkadotConfig, Keypair>;
+
+async fn setup() -> Result<(Connection, Instance)> {
+    let conn = SignedConnection::from_url(NODE_URL, dev::alice()).await?;
+    conn.upload_code(test_contract::upload().with_tx_status(TxStatus::InBlock))
+        .await?;
+
+    // The chain outlives a single test run, so every instance needs a fresh salt.
+    let salt = SystemTime::now()
+        .duration_since(UNIX_EPOCH)?
+        .as_nanos()
+        .to_le_bytes()
+        .to_vec();
+    let instance = conn
+        .instantiate(
+            Instance::default()
+                .with_salt(salt)
+                .with_tx_status(TxStatus::InBlock),
+        )
+        .await?
+        .result
+        .to_account_id()
+        .into();
+
+    Ok((conn, instance))
+}
+
+#[tokio::test]
+async fn test_upload() -> Result<()> {
+    let conn = Connection::from_url(NODE_URL, dev::alice()).await?;
+    let code_hash = conn
+        .upload_code(test_contract::upload().with_tx_status(TxStatus::InBlock))
+        .await?
+        .code_hash;
+    assert!(code_hash.as_ref() == test_contract::CODE_HASH);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_simple_integer_messages() -> Result<()> {
+    let (conn, instance) = setup().await?;
+
+    let _r = conn
+        .execute(instance.set_u32(42).with_tx_status(TxStatus::InBlock))
+        .await?;
+
+    assert!(conn.query(instance.get_u32()).await?.result? == 42);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_events() -> Result<()> {
+    let (conn, instance) = setup().await?;
+
+    let result = conn
+        .execute(
+            instance
+                .receive_value()
+                .with_value(123)
+                .with_tx_status(TxStatus::InBlock),
+        )
+        .await?;
+
+    let events = ContractEvents::from_iter(&result.events, instance);
+    assert!(events == [Ok(test_contract::event::Event::Received { value: 123 })]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_submitted_without_events() -> Result<()> {
+    let (conn, instance) = setup().await?;
+
+    let result = conn
+        .execute(
+            instance
+                .generate_events()
+                .with_tx_status(TxStatus::Submitted),
+        )
+        .await?;
+
+    assert!(result.events.is_empty());
+    Ok(())
+}
+
+/// Written against `AsyncConnection` only, so that the spawned task checks its futures are `Send`.
+async fn set_u32<C: AsyncConnection>(conn: Arc<C>, instance: Instance, value: u32) -> Result<()> {
+    conn.execute(instance.set_u32(value).with_tx_status(TxStatus::InBlock))
+        .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_spawned_task() -> Result<()> {
+    let (conn, instance) = setup().await?;
+    let conn = Arc::new(conn);
+
+    tokio::spawn(set_u32(conn.clone(), instance, 7)).await??;
+
+    assert!(conn.query(instance.get_u32()).await?.result? == 7);
+    Ok(())
+}
@@ -3,6 +3,10 @@
 
 #[ink::contract]
 mod test_contract {
+    use bitvec::{
+        order::{Lsb0, Msb0},
+        vec::BitVec,
+    };
     use ink::prelude::{vec, vec::Vec};
     #[cfg(feature = "std")]
     use ink::storage::traits::StorageLayout;
@@ -42,6 +46,13 @@ mod test_contract {
         value: Balance,
     }
 
+    /// An event whose signature is too long to be used as a topic directly, so it gets hashed.
+    #[ink(event)]
+    pub struct EventWithALongSignature {
+        #[ink(topic)]
+        value: u32,
+    }
+
     #[derive(Debug, Clone, Copy, Default, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
     pub struct Struct1 {
@@ -83,8 +94,68 @@ mod test_contract {
         a: u32,
     }
 
+    /// A module with a type named the same as `Struct1` above, to test that the generated definitions don't clash.
+    pub mod nested {
+        #[derive(Debug, Clone, Copy, Default, scale::Encode, scale::Decode)]
+        #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+        pub struct Struct1 {
+            pub value: bool,
+        }
+    }
+
+    /// A generic struct, used with different type arguments in the messages.
+    #[derive(Debug, Clone, Copy, Default, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Pair<T> {
+        a: T,
+        b: T,
+        count: u32,
+    }
+
+    #[derive(Debug, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Either<L, R> {
+        Left(L),
+        Right(R),
+    }
+
+    /// A generic struct with two parameters, used with the same type argument for both.
+    #[derive(Debug, Clone, Copy, Default, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Entry<K, V> {
+        key: K,
+        value: V,
+        version: (K, u32),
+    }
+
     type NewType1 = u32;
 
+    /// A 256-bit unsigned integer, which the metadata describes with the `u256` primitive.
+    #[derive(Debug, Clone, Copy, Default, scale::Encode, scale::Decode)]
+    pub struct U256([u8; 32]);
+
+    /// A 256-bit signed integer, which the metadata describes with the `i256` primitive.
+    #[derive(Debug, Clone, Copy, Default, scale::Encode, scale::Decode)]
+    pub struct I256([u8; 32]);
+
+    #[cfg(feature = "std")]
+    impl scale_info::TypeInfo for U256 {
+        type Identity = Self;
+
+        fn type_info() -> scale_info::Type {
+            scale_info::TypeDefPrimitive::U256.into()
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl scale_info::TypeInfo for I256 {
+        type Identity = Self;
+
+        fn type_info() -> scale_info::Type {
+            scale_info::TypeDefPrimitive::I256.into()
+        }
+    }
+
     impl TestContract {
         /// Example docs for a constructor.
         /// They are multiline.
@@ -276,5 +347,128 @@ mod test_contract {
             let value = Self::env().transferred_value();
             Self::env().emit_event(Received { value });
         }
+
+        #[ink(message)]
+        pub fn generate_long_signature_event(&mut self) {
+            Self::env().emit_event(EventWithALongSignature {
+                value: self.u32_val,
+            });
+        }
+
+        #[ink(message, payable)]
+        pub fn get_transferred_value(&self) -> u128 {
+            Self::env().transferred_value()
+        }
+
+        #[ink(message)]
+        pub fn swap_pair(&self, pair: Pair<u32>) -> Pair<u32> {
+            Pair {
+                a: pair.b,
+                b: pair.a,
+                count: pair.count + 1,
+            }
+        }
+
+        #[ink(message)]
+        pub fn swap_bool_pair(&self, pair: Pair<bool>) -> Pair<bool> {
+            Pair {
+                a: pair.b,
+                b: pair.a,
+                count: pair.count + 1,
+            }
+        }
+
+        #[ink(message)]
+        pub fn get_nested_struct1(&self) -> nested::Struct1 {
+            nested::Struct1 {
+                value: self.bool_val,
+            }
+        }
+
+        #[ink(message)]
+        pub fn set_nested_struct1(&mut self, value: nested::Struct1) {
+            self.bool_val = value.value;
+        }
+
+        #[ink(message)]
+        pub fn invert_bits(&self, bits: BitVec<u8, Lsb0>) -> BitVec<u8, Lsb0> {
+            !bits
+        }
+
+        #[ink(message)]
+        pub fn reverse_bits(&self, bits: BitVec<u32, Msb0>) -> BitVec<u32, Msb0> {
+            let mut bits = bits;
+            bits.reverse();
+            bits
+        }
+
+        /// Returns the value as a little-endian, zero-extended `u256`.
+        #[ink(message)]
+        pub fn get_u256(&self, value: u128) -> U256 {
+            let mut bytes = [0u8; 32];
+            bytes[..16].copy_from_slice(&value.to_le_bytes());
+            U256(bytes)
+        }
+
+        /// Returns the value as a little-endian, sign-extended `i256`.
+        #[ink(message)]
+        pub fn get_i256(&self, value: i128) -> I256 {
+            let mut bytes = if value < 0 { [0xffu8; 32] } else { [0u8; 32] };
+            bytes[..16].copy_from_slice(&value.to_le_bytes());
+            I256(bytes)
+        }
+
+        /// Returns the lowest 128 bits of the value.
+        #[ink(message)]
+        pub fn truncate_i256(&self, value: I256) -> i128 {
+            let mut bytes = [0u8; 16];
+            bytes.copy_from_slice(&value.0[..16]);
+            i128::from_le_bytes(bytes)
+        }
+
+        /// Prints `count` lines to the debug buffer.
+        #[ink(message)]
+        pub fn print_debug_lines(&mut self, count: u32) {
+            for i in 0..count {
+                ink::env::debug_println!("line {}", i);
+            }
+        }
+
+        /// Prints `count` lines to the debug buffer and fails, which reverts the call.
+        #[ink(message)]
+        pub fn print_debug_lines_and_fail(&mut self, count: u32) -> Result<(), u32> {
+            self.print_debug_lines(count);
+            Err(count)
+        }
+
+        #[ink(message)]
+        pub fn swap_either(&self, either: Either<u32, u32>) -> Either<u32, u32> {
+            match either {
+                Either::Left(value) => Either::Right(value),
+                Either::Right(value) => Either::Left(value),
+            }
+        }
+
+        #[ink(message)]
+        pub fn swap_entry(&self, entry: Entry<u32, u32>) -> Entry<u32, u32> {
+            Entry {
+                key: entry.value,
+                value: entry.key,
+                version: (entry.value, entry.version.1 + 1),
+            }
+        }
+
+        #[ink(message)]
+        pub fn get_either(&self, left: bool) -> Either<u32, Vec<Pair<u32>>> {
+            if left {
+                Either::Left(self.u32_val)
+            } else {
+                Either::Right(vec![Pair {
+                    a: self.u32_val,
+                    b: self.u32_val,
+                    count: 0,
+                }])
+            }
+        }
     }
 }